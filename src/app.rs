@@ -1,79 +1,498 @@
+use std::{
+    error, fmt, fs, io,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+pub use config::{Config, ConfigWatcher};
+pub use export::Format;
+pub use logger::Logger;
+pub use print::PrintScreen;
+pub use screen::ScreenOptions;
+pub use view::{by_name as view_by_name, parse_env, InspectView, View, NAMES as VIEW_NAMES};
 use screen::Screen;
 
 use self::{
-    screen::{ScreenApi, ViewLoader},
-    view::HomeView,
+    buffer::CellBuffer,
+    clock::FrameClock,
+    config::KeyBindings,
+    event::Event,
+    renderer::{Area, Renderer, Themed},
+    screen::ScreenApi,
+    ui::{Borders, Color, Label, Padding, Role, Style, Surface, Theme},
+    view::{Action, Navigation},
 };
 
+mod buffer;
+mod clock;
+mod config;
 mod event;
+mod export;
+#[cfg(test)]
+mod headless;
+mod logger;
+mod palette;
+mod print;
 mod screen;
+#[cfg(test)]
+mod snapshot;
 mod view;
 mod renderer;
 mod ui;
 
+#[cfg(any(feature = "ratatui", feature = "crossterm"))]
+mod term;
 #[cfg(feature = "ratatui")]
 mod tui;
 
-use view::View;
-
-pub struct App {
+pub struct App<S: ScreenApi = Screen> {
     running: bool,
-    screen: Screen,
-    view: Box<dyn View>,
+    screen: S,
+    /**
+     * Navigation stack, the last view is the one on screen. The root view is never popped while
+     * the app is running.
+     */
+    views: Vec<Box<dyn View>>,
+    clock: FrameClock,
+    /**
+     * Last recoverable error, shown until the next key press
+     */
+    status: Option<String>,
+    /**
+     * Size of the last frame drawn, exports are drawn at the same size
+     */
+    frame_size: Area,
+    /**
+     * Where Ctrl+S saves the current frame
+     */
+    export_dir: PathBuf,
+    logger: Logger,
+    keys: KeyBindings,
+    theme: Theme,
+    /**
+     * Reloads the config file when it changes, if the app was started from one
+     */
+    watcher: Option<ConfigWatcher>,
 }
 
 impl App {
-    pub fn new() -> Result<Self, Error> {
-        let screen = Screen::new()?;
-        Ok(Self {
+    /**
+     * App on the terminal, starting on `root_view`
+     */
+    pub fn new(options: &ScreenOptions, root_view: Box<dyn View>) -> Result<Self, Error> {
+        let screen = Screen::new(options)?;
+        Ok(Self::with_root_view(screen, root_view))
+    }
+}
+
+impl<S: ScreenApi> App<S> {
+    #[cfg(test)]
+    pub fn with_screen(screen: S) -> Self {
+        Self::with_root_view(screen, Box::new(view::HomeView::new()))
+    }
+
+    /**
+     * Starts with the default settings, see `with_config`
+     */
+    pub fn with_root_view(screen: S, root_view: Box<dyn View>) -> Self {
+        let config = Config::new();
+        Self {
             running: false,
             screen,
-            view: Box::new(HomeView::new()),
-        })
+            views: vec![root_view],
+            status: None,
+            frame_size: Area {
+                x: 0,
+                y: 0,
+                cols: 0,
+                rows: 0,
+            },
+            export_dir: PathBuf::from("."),
+            logger: Logger::none(),
+            keys: config.keys,
+            theme: Theme::dark(),
+            watcher: None,
+            clock: FrameClock::from(config.tick_rate, config.max_fps, Instant::now()),
+        }
+    }
+
+    /**
+     * Set how often a frame is drawn when no input arrives, and the upper bound on frames per
+     * second (0 for unlimited)
+     */
+    pub fn with_timing(mut self, tick_rate: Duration, max_fps: u32) -> Self {
+        self.clock = FrameClock::from(tick_rate, max_fps, Instant::now());
+        self
     }
 
+    /**
+     * Use the timing, key bindings and theme of `config`. An unknown theme leaves the current one
+     * in place, `Config::get_theme` tells why.
+     */
+    pub fn with_config(mut self, config: &Config) -> Self {
+        self.set_config(config);
+        self.with_timing(config.tick_rate, config.max_fps)
+    }
+
+    /**
+     * Re-apply the config whenever `watcher` sees the file change
+     */
+    pub fn with_config_watcher(mut self, watcher: ConfigWatcher) -> Self {
+        self.watcher = Some(watcher);
+        self
+    }
+
+    pub fn with_logger(mut self, logger: Logger) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /**
+     * The screen is closed even when the loop fails, so the error is reported on a usable terminal
+     */
     pub fn run(&mut self) -> Result<(), Error> {
-        self.start()?;
+        self.logger.log("started");
+        let result = self.start().and_then(|_| self.run_loop());
+        let close_result = self.close();
+        let result = result.and(close_result);
+        match &result {
+            Ok(()) => self.logger.log("stopped"),
+            Err(err) => self.logger.log(format_args!("stopped: {err}")),
+        }
+        result
+    }
+
+    /**
+     * Draw a single frame without waiting for input, for screens that are not interactive
+     */
+    pub fn render_once(&mut self) -> Result<(), Error> {
+        let result = self.start().and_then(|_| self.render());
+        let close_result = self.close();
+        result.and(close_result)
+    }
+
+    fn run_loop(&mut self) -> Result<(), Error> {
+        // Drawing before waiting gets the first frame on screen without any input
         while self.running {
+            if self.clock.should_render(Instant::now()) {
+                self.render()?;
+            }
             self.update()?;
-            self.render();
         }
-        self.close()?;
         Ok(())
     }
 
     fn start(&mut self) -> Result<(), Error> {
         self.running = true;
-        Ok(())
+        let result = self.current_view().init();
+        self.recover(result, ())
     }
 
     fn update(&mut self) -> Result<(), Error> {
-        let update_action = self.screen.update(&mut self.view)?;
-        self.running = update_action.get_keep_running();
-        if let Some(view_loader) = update_action.get_view_loader() {
-            self.load_view(view_loader);
+        let timeout = self.clock.timeout(Instant::now());
+        if let Some(event) = self.screen.poll_event(timeout)? {
+            self.handle_event(event)?;
+        }
+        if let Some(reloaded) = self.watcher.as_mut().and_then(|w| w.poll(Instant::now())) {
+            self.reload_config(reloaded)?;
+        }
+        if self.current_view().needs_redraw() {
+            self.clock.request_redraw();
         }
         Ok(())
     }
 
-    fn render(&mut self) {
-        self.screen.render(&mut self.view);
+    fn handle_event(&mut self, event: Event) -> Result<(), Error> {
+        match &event {
+            // Raw mode swallows SIGINT, so the quit key stays a way out no matter what the view does
+            Event::Key(key) if *key == self.keys.quit => {
+                self.running = false;
+                return Ok(());
+            }
+            Event::Key(key) if *key == self.keys.export => return self.export_frame(),
+            // A key press acknowledges the message in the status bar
+            Event::Key(_) if self.status.take().is_some() => self.clock.request_redraw(),
+            // Whatever was on the terminal is gone after a resize
+            Event::Resize { .. } => self.clock.request_redraw(),
+            _ => {}
+        }
+
+        let result = self.current_view().update(&event);
+        let action = self.recover(result, Action::None)?;
+        self.apply(action, self.views.len() - 1)
     }
 
+    /**
+     * Switch to a config read again from its file. A broken file keeps the current settings and
+     * is reported in the status bar, so it can be fixed without restarting.
+     */
+    fn reload_config(&mut self, reloaded: Result<Config, config::Error>) -> Result<(), Error> {
+        let checked = reloaded
+            .map_err(|err| err.to_string())
+            .and_then(|config| config.get_theme().map(|_| config));
+        let config = match checked {
+            Ok(config) => config,
+            Err(message) => {
+                self.logger.log(format_args!("config not reloaded: {message}"));
+                self.status = Some(message);
+                self.clock.request_redraw();
+                return Ok(());
+            }
+        };
+        self.logger.log("config reloaded");
+        self.set_config(&config);
+        self.clock = FrameClock::from(config.tick_rate, config.max_fps, Instant::now());
+
+        // Views pushed while the others are told were created with the new config already, and
+        // views may close while they are told, e.g. a dialog that no longer applies
+        let count = self.views.len();
+        let mut index = 0;
+        while index < count.min(self.views.len()) {
+            let result = self.views[index].on_config_change(&config);
+            let action = self.recover(result, Action::None)?;
+            // Navigation always acts on the top of the stack, so only the top view may navigate
+            if matches!(action, Action::Navigate(_)) && index + 1 != self.views.len() {
+                self.logger.log(format_args!("navigation of covered view {index} ignored"));
+            } else {
+                self.apply(action, index)?;
+            }
+            index += 1;
+        }
+        self.clock.request_redraw();
+        Ok(())
+    }
+
+    /**
+     * Take the key bindings and theme of `config`, its timing comes with a new clock
+     */
+    fn set_config(&mut self, config: &Config) {
+        self.keys = config.keys;
+        if let Ok(theme) = config.get_theme() {
+            self.theme = theme;
+        }
+    }
+
+    /**
+     * Carry out an action returned by the view at `sender` in the navigation stack
+     */
+    fn apply(&mut self, action: Action, sender: usize) -> Result<(), Error> {
+        match action {
+            Action::None => {}
+            Action::Redraw => self.clock.request_redraw(),
+            Action::Quit => self.running = false,
+            Action::Navigate(navigation) => self.navigate(navigation)?,
+            Action::Custom(message) => {
+                // The root view has nobody to send messages to
+                if sender > 0 {
+                    let result = self.views[sender - 1].on_message(message);
+                    let action = self.recover(result, Action::None)?;
+                    self.apply(action, sender - 1)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * Recoverable view errors are shown in the status bar and replaced by `fallback`, fatal ones
+     * stop the app
+     */
+    fn recover<T>(&mut self, result: Result<T, view::Error>, fallback: T) -> Result<T, Error> {
+        match result {
+            Err(err) if !err.is_fatal() => {
+                self.logger.log(format_args!("recovered: {err}"));
+                self.status = Some(err.to_string());
+                self.clock.request_redraw();
+                Ok(fallback)
+            }
+            result => Ok(result?),
+        }
+    }
+
+    fn render(&mut self) -> Result<(), Error> {
+        let view = self.views.last_mut().unwrap();
+        let status = self.status.as_deref();
+        let theme = &self.theme;
+        let frame_size = &mut self.frame_size;
+        let mut result = Ok(());
+        self.screen.render(&mut |renderer: &mut dyn Renderer| {
+            *frame_size = renderer.frame_size();
+            result = draw_frame(view.as_mut(), status, theme, renderer);
+        })?;
+        self.recover(result, ())
+    }
+
+    /**
+     * Save what is on screen as HTML and SVG, the outcome is reported in the status bar
+     */
+    fn export_frame(&mut self) -> Result<(), Error> {
+        let mut buffer = CellBuffer::empty(self.frame_size);
+        let view = self.views.last_mut().unwrap();
+        let result = draw_frame(view.as_mut(), self.status.as_deref(), &self.theme, &mut buffer);
+        self.recover(result, ())?;
+
+        let millis = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        // Exports never overwrite each other, a name already taken gets a counter
+        let (html, svg) = (0..)
+            .map(|count| match count {
+                0 => self.export_dir.join(format!("visualnv-{millis}")),
+                count => self.export_dir.join(format!("visualnv-{millis}-{count}")),
+            })
+            .map(|stem| (stem.with_extension("html"), stem.with_extension("svg")))
+            .find(|(html, svg)| !html.exists() && !svg.exists())
+            .unwrap();
+        let written: io::Result<()> = fs::write(&html, Format::Html.export(&buffer))
+            .and_then(|_| fs::write(&svg, Format::Svg.export(&buffer)));
+        let message = match written {
+            Ok(()) => format!("Saved the frame to {} and {}", html.display(), svg.display()),
+            Err(err) => format!("Saving the frame failed: {err}"),
+        };
+        self.logger.log(&message);
+        self.status = Some(message);
+        self.clock.request_redraw();
+        Ok(())
+    }
+
+    /**
+     * Every view is closed even if some fail, the first fatal error is reported
+     */
     fn close(&mut self) -> Result<(), Error> {
+        let mut result = Ok(());
+        while let Some(mut view) = self.views.pop() {
+            if let Err(err) = view.close() {
+                if err.is_fatal() && result.is_ok() {
+                    result = Err(err.into());
+                }
+            }
+        }
         self.screen.close()?;
+        result
+    }
+
+    fn current_view(&mut self) -> &mut Box<dyn View> {
+        self.views.last_mut().unwrap()
+    }
+
+    fn navigate(&mut self, navigation: Navigation) -> Result<(), Error> {
+        match navigation {
+            Navigation::Push(view) => self.push_view(view)?,
+            Navigation::Pop => {
+                if self.views.len() == 1 {
+                    self.running = false;
+                } else {
+                    self.pop_view()?;
+                }
+            }
+            Navigation::Replace(view) => {
+                // Closing and initializing in place keeps the stack from ever being empty
+                let result = self.current_view().close();
+                self.recover(result, ())?;
+                *self.current_view() = view;
+                let result = self.current_view().init();
+                self.recover(result, ())?;
+            }
+            Navigation::PopToRoot => {
+                while self.views.len() > 1 {
+                    self.pop_view()?;
+                }
+            }
+        }
+        self.clock.request_redraw();
         Ok(())
     }
 
-    fn load_view(&mut self, view_loader: ViewLoader) {
-        self.view = view_loader();
-        let _ = self.view.init();
+    fn push_view(&mut self, mut view: Box<dyn View>) -> Result<(), Error> {
+        let result = view.init();
+        self.views.push(view);
+        self.recover(result, ())
+    }
+
+    fn pop_view(&mut self) -> Result<(), Error> {
+        match self.views.pop() {
+            Some(mut view) => {
+                let result = view.close();
+                self.recover(result, ())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/**
+ * The current view on the theme's background, with the status bar on top
+ */
+fn draw_frame(
+    view: &mut dyn View,
+    status: Option<&str>,
+    theme: &Theme,
+    renderer: &mut dyn Renderer,
+) -> Result<(), view::Error> {
+    let renderer = &mut Themed::from(renderer, theme);
+    renderer.fill_rect(renderer.frame_size(), theme.background);
+    let result = view.render(renderer);
+    if let Some(status) = status {
+        render_status_bar(renderer, status);
     }
+    result
+}
+
+/**
+ * Draw `message` on the bottom row of the frame, over whatever the view drew there
+ */
+fn render_status_bar(renderer: &mut dyn Renderer, message: &str) {
+    let frame_size = renderer.frame_size();
+    if frame_size.rows == 0 {
+        return;
+    }
+    let area = Area {
+        x: frame_size.x,
+        y: frame_size.y + frame_size.rows - 1,
+        cols: frame_size.cols,
+        rows: 1,
+    };
+
+    let mut status_bar = Surface::from(
+        Padding::none(),
+        |internal_renderer: &mut dyn Renderer, content_area: &Area| {
+            internal_renderer.render(
+                &mut Label::from(message),
+                *content_area,
+                &Style::from(Borders::none(), Color::Inherit, Role::Background),
+            );
+        },
+    );
+    renderer.render(
+        &mut status_bar,
+        area,
+        &Style::from(Borders::none(), Role::Error, Role::Background),
+    );
 }
 
 #[derive(Debug)]
 pub enum Error {
     ScreenError(screen::Error),
+    ViewError(view::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ScreenError(_) => f.write_str("the screen failed"),
+            Self::ViewError(_) => f.write_str("a view failed"),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::ScreenError(err) => Some(err),
+            Self::ViewError(err) => Some(err),
+        }
+    }
 }
 
 impl From<screen::Error> for Error {
@@ -81,3 +500,507 @@ impl From<screen::Error> for Error {
         Self::ScreenError(value)
     }
 }
+
+impl From<view::Error> for Error {
+    fn from(value: view::Error) -> Self {
+        Self::ViewError(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+    use super::*;
+    use crate::app::{
+        event::{Key, KeyEvent, Modifiers},
+        renderer::Widget,
+        ui::RGBA,
+        view::{self, Message},
+    };
+
+    /**
+     * Screen that never blocks. Each scripted entry is one loop iteration, either an event or an
+     * idle wait. Once the script runs out the screen sends Ctrl+C to stop the app.
+     */
+    struct ScriptedScreen {
+        script: VecDeque<Option<Event>>,
+        timeouts: Vec<Duration>,
+        renders: usize,
+        fail_at: Option<usize>,
+        closed: bool,
+    }
+
+    impl ScriptedScreen {
+        fn from(script: Vec<Option<Event>>) -> Self {
+            Self {
+                script: script.into(),
+                timeouts: Vec::new(),
+                renders: 0,
+                fail_at: None,
+                closed: false,
+            }
+        }
+
+        fn idle(iterations: usize) -> Self {
+            Self::from((0..iterations).map(|_| None).collect())
+        }
+
+        fn keys(keys: &str) -> Self {
+            Self::from(keys.chars().map(|c| Some(Event::Key(KeyEvent::from(Key::Char(c))))).collect())
+        }
+    }
+
+    impl ScreenApi for ScriptedScreen {
+        fn new(_: &ScreenOptions) -> screen::Result<Self> {
+            Ok(Self::idle(0))
+        }
+
+        fn poll_event(&mut self, timeout: Duration) -> screen::Result<Option<Event>> {
+            self.timeouts.push(timeout);
+            if self.fail_at == Some(self.timeouts.len() - 1) {
+                return Err(std::io::Error::other("scripted failure").into());
+            }
+            match self.script.pop_front() {
+                Some(event) => Ok(event),
+                None => Ok(Some(Event::Key(
+                    KeyEvent::from(Key::Char('c')).with_modifiers(Modifiers::CTRL),
+                ))),
+            }
+        }
+
+        fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> screen::Result<()> {
+            self.renders += 1;
+            draw(&mut NullRenderer);
+            Ok(())
+        }
+
+        fn close(&mut self) -> screen::Result<()> {
+            self.closed = true;
+            Ok(())
+        }
+    }
+
+    struct NullRenderer;
+
+    impl Renderer for NullRenderer {
+        fn frame_size(&self) -> Area {
+            Area {
+                x: 0,
+                y: 0,
+                cols: 80,
+                rows: 24,
+            }
+        }
+
+        fn put_str(&mut self, _: u16, _: u16, _: &str, _: RGBA, _: RGBA) {}
+
+        fn fill_rect(&mut self, _: Area, _: RGBA) {}
+
+        fn set_cursor(&mut self, _: u16, _: u16) {}
+
+        fn render(&mut self, _: &mut dyn Widget, _: Area, _: &Style) {}
+    }
+
+    type Log = Rc<RefCell<Vec<String>>>;
+
+    /**
+     * View that records its lifecycle and messages and acts on key presses: `p` pushes, `b` pops,
+     * `r` replaces, `h` goes back to the root, `m` messages the view below and `q` quits.
+     * `e` fails with a recoverable error and `f` with a fatal one.
+     * A view receiving a message pops the sender.
+     */
+    struct LoggingView {
+        name: String,
+        log: Log,
+    }
+
+    impl LoggingView {
+        fn boxed(name: &str, log: &Log) -> Box<dyn View> {
+            Box::new(Self {
+                name: String::from(name),
+                log: log.clone(),
+            })
+        }
+
+        fn record(&self, what: &str) {
+            self.log.borrow_mut().push(format!("{} {what}", self.name));
+        }
+    }
+
+    impl View for LoggingView {
+        fn init(&mut self) -> Result<(), view::Error> {
+            self.record("init");
+            Ok(())
+        }
+
+        fn update(&mut self, event: &Event) -> Result<Action, view::Error> {
+            let Event::Key(key) = event else {
+                return Ok(Action::None);
+            };
+            let child_name = format!("{}>", self.name);
+            let replacement_name = format!("{}'", self.name);
+            Ok(match key.get_key() {
+                Key::Char('p') => Action::Navigate(Navigation::Push(Self::boxed(&child_name, &self.log))),
+                Key::Char('b') => Action::Navigate(Navigation::Pop),
+                Key::Char('r') => Action::Navigate(Navigation::Replace(Self::boxed(&replacement_name, &self.log))),
+                Key::Char('h') => Action::Navigate(Navigation::PopToRoot),
+                Key::Char('m') => Action::Custom(Box::new(self.name.clone())),
+                Key::Char('q') => Action::Quit,
+                Key::Char('e') => return Err(view::Error::message(format!("{} failed", self.name))),
+                Key::Char('f') => return Err(view::Error::invalid_state(format!("{} broke", self.name))),
+                _ => Action::None,
+            })
+        }
+
+        fn on_message(&mut self, message: Message) -> Result<Action, view::Error> {
+            let sender = message.downcast::<String>().unwrap();
+            self.record(&format!("message from {sender}"));
+            Ok(Action::Navigate(Navigation::Pop))
+        }
+
+        fn on_config_change(&mut self, _: &Config) -> Result<Action, view::Error> {
+            self.record("config changed");
+            Ok(Action::None)
+        }
+
+        fn render(&mut self, _: &mut dyn Renderer) -> Result<(), view::Error> {
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), view::Error> {
+            self.record("close");
+            Ok(())
+        }
+    }
+
+    /**
+     * View that pushes a `LoggingView` named `pushed` whenever the config changes
+     */
+    struct PushingView {
+        log: Log,
+    }
+
+    impl View for PushingView {
+        fn init(&mut self) -> Result<(), view::Error> {
+            Ok(())
+        }
+
+        fn update(&mut self, _: &Event) -> Result<Action, view::Error> {
+            Ok(Action::None)
+        }
+
+        fn on_config_change(&mut self, _: &Config) -> Result<Action, view::Error> {
+            self.log.borrow_mut().push(String::from("pusher config changed"));
+            Ok(Action::Navigate(Navigation::Push(LoggingView::boxed("pushed", &self.log))))
+        }
+
+        fn render(&mut self, _: &mut dyn Renderer) -> Result<(), view::Error> {
+            Ok(())
+        }
+
+        fn close(&mut self) -> Result<(), view::Error> {
+            Ok(())
+        }
+    }
+
+    fn try_run_keys(keys: &str) -> (App<ScriptedScreen>, Vec<String>, Result<(), Error>) {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::with_root_view(ScriptedScreen::keys(keys), LoggingView::boxed("root", &log));
+        let result = app.run();
+        let entries = log.borrow().clone();
+        (app, entries, result)
+    }
+
+    fn run_keys(keys: &str) -> (App<ScriptedScreen>, Vec<String>) {
+        let (app, log, result) = try_run_keys(keys);
+        result.unwrap();
+        (app, log)
+    }
+
+    #[test]
+    fn idle_iterations_do_not_render() {
+        let tick_rate = Duration::from_secs(3600);
+        let mut app = App::with_screen(ScriptedScreen::idle(100)).with_timing(tick_rate, 0);
+        app.run().unwrap();
+
+        // Only the initial frame is drawn
+        assert_eq!(app.screen.renders, 1);
+        // The last poll is the scripted Ctrl+C
+        assert_eq!(app.screen.timeouts.len(), 101);
+        assert!(app.screen.timeouts.iter().all(|timeout| *timeout > Duration::from_secs(3000)));
+    }
+
+    #[test]
+    fn events_trigger_renders() {
+        let tick_rate = Duration::from_secs(3600);
+        let key = || Some(Event::Key(KeyEvent::from(Key::Enter)));
+        let script = vec![None, key(), None, None, key(), key(), None];
+        let mut app = App::with_screen(ScriptedScreen::from(script)).with_timing(tick_rate, 0);
+        app.run().unwrap();
+
+        assert_eq!(app.screen.renders, 4);
+    }
+
+    #[test]
+    fn ticks_trigger_renders() {
+        let mut app = App::with_screen(ScriptedScreen::idle(3)).with_timing(Duration::ZERO, 0);
+        app.run().unwrap();
+
+        // Every iteration lands on a tick, the loop stops on the fourth poll
+        assert_eq!(app.screen.renders, 4);
+    }
+
+    #[test]
+    fn unhandled_events_do_not_render() {
+        let tick_rate = Duration::from_secs(3600);
+        let key = || Some(Event::Key(KeyEvent::from(Key::Char('x'))));
+        let mut app = App::with_screen(ScriptedScreen::from(vec![key(), key(), key()]))
+            .with_timing(tick_rate, 0);
+        app.run().unwrap();
+
+        assert_eq!(app.screen.renders, 1);
+    }
+
+    #[test]
+    fn screen_is_closed_on_error() {
+        let mut screen = ScriptedScreen::idle(10);
+        screen.fail_at = Some(3);
+        let mut app = App::with_screen(screen);
+
+        assert!(app.run().is_err());
+        assert!(app.screen.closed);
+        assert_eq!(app.screen.timeouts.len(), 4);
+    }
+
+    #[test]
+    fn push_and_pop() {
+        let (app, log) = run_keys("ppb");
+
+        assert_eq!(
+            log,
+            vec![
+                "root init",
+                "root> init",
+                "root>> init",
+                "root>> close",
+                // Views still on the stack are closed top to bottom when the app stops
+                "root> close",
+                "root close",
+            ]
+        );
+        assert!(app.views.is_empty());
+    }
+
+    #[test]
+    fn replace() {
+        let (_, log) = run_keys("pr");
+
+        assert_eq!(
+            log,
+            vec!["root init", "root> init", "root> close", "root>' init", "root>' close", "root close"]
+        );
+    }
+
+    #[test]
+    fn pop_to_root() {
+        let (_, log) = run_keys("ppph");
+
+        assert_eq!(
+            log,
+            vec![
+                "root init",
+                "root> init",
+                "root>> init",
+                "root>>> init",
+                "root>>> close",
+                "root>> close",
+                "root> close",
+                "root close",
+            ]
+        );
+    }
+
+    #[test]
+    fn quit_action() {
+        let (app, log) = run_keys("pqp");
+
+        assert_eq!(log, vec!["root init", "root> init", "root> close", "root close"]);
+        assert_eq!(app.screen.script.len(), 1);
+    }
+
+    #[test]
+    fn custom_messages_go_to_the_view_below() {
+        let (_, log) = run_keys("ppm");
+
+        assert_eq!(
+            log,
+            vec![
+                "root init",
+                "root> init",
+                "root>> init",
+                "root> message from root>>",
+                "root>> close",
+                "root> close",
+                "root close",
+            ]
+        );
+    }
+
+    #[test]
+    fn root_messages_are_dropped() {
+        let (_, log) = run_keys("m");
+
+        assert_eq!(log, vec!["root init", "root close"]);
+    }
+
+    #[test]
+    fn popping_the_root_quits() {
+        let (app, log) = run_keys("bp");
+
+        // The push never happens, the app stopped on the first key
+        assert_eq!(log, vec!["root init", "root close"]);
+        assert_eq!(app.screen.script.len(), 1);
+    }
+
+    #[test]
+    fn recoverable_errors_go_to_the_status_bar() {
+        let (app, log) = run_keys("pe");
+
+        assert_eq!(app.status.as_deref(), Some("root> failed"));
+        // The app kept running until the script ran out
+        assert!(app.screen.script.is_empty());
+        assert_eq!(log, vec!["root init", "root> init", "root> close", "root close"]);
+    }
+
+    #[test]
+    fn key_presses_clear_the_status_bar() {
+        let (app, _) = run_keys("ex");
+
+        assert_eq!(app.status, None);
+    }
+
+    #[test]
+    fn fatal_errors_stop_the_app() {
+        let (app, log, result) = try_run_keys("pfp");
+
+        match result {
+            Err(Error::ViewError(err)) => assert_eq!(err.to_string(), "invalid state: root> broke"),
+            _ => panic!("expected a view error"),
+        }
+        assert!(app.screen.closed);
+        assert_eq!(app.screen.script.len(), 1);
+        assert_eq!(log, vec!["root init", "root> init", "root> close", "root close"]);
+    }
+
+    #[test]
+    fn ctrl_s_exports_the_frame() {
+        let export_dir = std::env::temp_dir().join(format!("visualnv-export-{}", std::process::id()));
+        fs::create_dir_all(&export_dir).unwrap();
+        let ctrl_s = Event::Key(KeyEvent::from(Key::Char('s')).with_modifiers(Modifiers::CTRL));
+        let mut app = App::with_screen(ScriptedScreen::from(vec![Some(ctrl_s)]));
+        app.export_dir = export_dir.clone();
+        app.run().unwrap();
+
+        let mut files: Vec<PathBuf> = fs::read_dir(&export_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        let html = fs::read_to_string(&files[0]).unwrap();
+        let svg = fs::read_to_string(&files[1]).unwrap();
+        fs::remove_dir_all(&export_dir).unwrap();
+
+        assert!(app.status.unwrap().starts_with("Saved the frame to"));
+        assert_eq!(files.len(), 2);
+        assert!(html.contains("This is some text"));
+        assert!(svg.contains("This is some text"));
+    }
+
+    #[test]
+    fn exports_never_overwrite_each_other() {
+        let export_dir = std::env::temp_dir().join(format!("visualnv-exports-{}", std::process::id()));
+        fs::create_dir_all(&export_dir).unwrap();
+        let ctrl_s = || Some(Event::Key(KeyEvent::from(Key::Char('s')).with_modifiers(Modifiers::CTRL)));
+        let mut app = App::with_screen(ScriptedScreen::from(vec![ctrl_s(), ctrl_s()]));
+        app.export_dir = export_dir.clone();
+        app.run().unwrap();
+
+        let files = fs::read_dir(&export_dir).unwrap().count();
+        fs::remove_dir_all(&export_dir).unwrap();
+
+        assert_eq!(files, 4);
+    }
+
+    /**
+     * Config file watched on every loop iteration, changed after the watcher saw it first
+     */
+    fn watched_config(name: &str, text: &str) -> (PathBuf, ConfigWatcher) {
+        let path = std::env::temp_dir().join(format!("visualnv-{name}-{}.toml", std::process::id()));
+        fs::write(&path, "").unwrap();
+        let touch = |path: &PathBuf, seconds| {
+            let modified = UNIX_EPOCH + Duration::from_secs(seconds);
+            fs::File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+        };
+        touch(&path, 1);
+        let watcher = ConfigWatcher::from(path.clone(), Duration::ZERO, Instant::now());
+        fs::write(&path, text).unwrap();
+        touch(&path, 2);
+        (path, watcher)
+    }
+
+    #[test]
+    fn config_changes_are_applied_while_running() {
+        let (path, watcher) = watched_config("reload", "theme = \"light\"\n[keys]\nquit = \"x\"\n");
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let x = Some(Event::Key(KeyEvent::from(Key::Char('x'))));
+        let screen = ScriptedScreen::from(vec![None, Some(Event::Key(KeyEvent::from(Key::Char('p')))), x]);
+        let mut app = App::with_root_view(screen, LoggingView::boxed("root", &log)).with_config_watcher(watcher);
+        app.run().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // The new quit key stopped the app before the script ran out
+        assert_eq!(app.keys.quit, KeyEvent::from(Key::Char('x')));
+        assert_eq!(app.theme, Theme::by_name("light").unwrap());
+        assert!(app.screen.script.is_empty());
+        assert_eq!(log.borrow()[..2], ["root init", "root config changed"]);
+    }
+
+    #[test]
+    fn broken_config_is_reported_in_the_status_bar() {
+        let (path, watcher) = watched_config("broken", "max_fps = ");
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::with_root_view(ScriptedScreen::idle(1), LoggingView::boxed("root", &log))
+            .with_config_watcher(watcher);
+        app.run().unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(app.keys, KeyBindings::new());
+        assert!(app.status.unwrap().starts_with(&format!("{}:1:11: ", path.display())));
+        assert_eq!(*log.borrow(), ["root init", "root close"]);
+    }
+
+    #[test]
+    fn only_the_top_view_navigates_on_config_change() {
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::with_root_view(ScriptedScreen::idle(0), Box::new(PushingView { log: log.clone() }));
+        app.push_view(LoggingView::boxed("top", &log)).unwrap();
+        app.reload_config(Ok(Config::new())).unwrap();
+
+        // The covered view cannot push over the top view
+        assert_eq!(app.views.len(), 2);
+        assert_eq!(*log.borrow(), ["top init", "pusher config changed", "top config changed"]);
+
+        let log: Log = Rc::new(RefCell::new(Vec::new()));
+        let mut app = App::with_root_view(ScriptedScreen::idle(0), LoggingView::boxed("root", &log));
+        app.push_view(Box::new(PushingView { log: log.clone() })).unwrap();
+        app.reload_config(Ok(Config::new())).unwrap();
+
+        // The pushed view is not told about the config it was created with
+        assert_eq!(app.views.len(), 3);
+        assert_eq!(
+            *log.borrow(),
+            ["root config changed", "pusher config changed", "pushed init"]
+        );
+    }
+}
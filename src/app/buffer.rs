@@ -0,0 +1,302 @@
+use super::{
+    renderer::{Area, Renderer, Widget},
+    ui::{Style, RGBA},
+};
+
+/**
+ * One terminal cell: the character drawn in it and its colors
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Cell {
+    pub symbol: char,
+    pub foreground: RGBA,
+    pub background: RGBA,
+}
+
+impl Cell {
+    pub fn empty() -> Self {
+        Self {
+            symbol: ' ',
+            foreground: RGBA::transparent(),
+            background: RGBA::transparent(),
+        }
+    }
+}
+
+/**
+ * Backend-neutral grid of cells covering `area`, stored row by row
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct CellBuffer {
+    area: Area,
+    cells: Vec<Cell>,
+    cursor: Option<(u16, u16)>,
+}
+
+impl CellBuffer {
+    pub fn empty(area: Area) -> Self {
+        Self {
+            area,
+            cells: vec![Cell::empty(); area.cols as usize * area.rows as usize],
+            cursor: None,
+        }
+    }
+
+    pub fn area(&self) -> Area {
+        self.area
+    }
+
+    /**
+     * Empty every cell and hide the cursor, so the buffer can be drawn again. Memory is only
+     * allocated when `area` has more cells than the buffer had so far.
+     */
+    pub fn reset(&mut self, area: Area) {
+        self.area = area;
+        self.cells.clear();
+        self.cells.resize(area.cols as usize * area.rows as usize, Cell::empty());
+        self.cursor = None;
+    }
+
+    /**
+     * Cell at the absolute position (x, y), `None` outside of the buffer
+     */
+    pub fn get(&self, x: u16, y: u16) -> Option<&Cell> {
+        self.index_of(x, y).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, x: u16, y: u16) -> Option<&mut Cell> {
+        self.index_of(x, y).map(|index| &mut self.cells[index])
+    }
+
+    /**
+     * Where the cursor was placed during the last render, `None` when it is hidden
+     */
+    pub fn cursor(&self) -> Option<(u16, u16)> {
+        self.cursor
+    }
+
+    #[cfg(test)]
+    /**
+     * Copy of the part of this buffer inside `area`, cells outside of this buffer are empty
+     */
+    pub fn crop(&self, area: Area) -> Self {
+        let mut cropped = Self::empty(area);
+        for y in area.y..area.y + area.rows {
+            for x in area.x..area.x + area.cols {
+                if let Some(cell) = self.get(x, y) {
+                    *cropped.get_mut(x, y).unwrap() = *cell;
+                }
+            }
+        }
+        cropped
+    }
+
+    /**
+     * Characters of row `y`, trailing blanks included
+     */
+    pub fn row_text(&self, y: u16) -> String {
+        (self.area.x..self.area.x + self.area.cols)
+            .filter_map(|x| self.get(x, y))
+            .map(|cell| cell.symbol)
+            .collect()
+    }
+
+    #[cfg(test)]
+    /**
+     * All rows joined with new lines
+     */
+    pub fn to_text(&self) -> String {
+        (self.area.y..self.area.y + self.area.rows)
+            .map(|y| self.row_text(y))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn index_of(&self, x: u16, y: u16) -> Option<usize> {
+        if !self.area.contains(x, y) {
+            return None;
+        }
+        let col = (x - self.area.x) as usize;
+        let row = (y - self.area.y) as usize;
+        Some(row * self.area.cols as usize + col)
+    }
+}
+
+impl Renderer for CellBuffer {
+    fn frame_size(&self) -> Area {
+        self.area
+    }
+
+    fn put_str(&mut self, x: u16, y: u16, text: &str, foreground: RGBA, background: RGBA) {
+        for (x, symbol) in (x..=u16::MAX).zip(text.chars()) {
+            if let Some(cell) = self.get_mut(x, y) {
+                cell.symbol = symbol;
+                cell.background = background.over(cell.background);
+                // The text is blended into its own background, a transparent foreground keeps
+                // the one already there
+                if !foreground.is_transparent() {
+                    cell.foreground = foreground.over(cell.background);
+                }
+            }
+        }
+    }
+
+    fn fill_rect(&mut self, area: Area, background: RGBA) {
+        if background.is_transparent() {
+            return;
+        }
+        for y in area.y..area.y.saturating_add(area.rows) {
+            for x in area.x..area.x.saturating_add(area.cols) {
+                if let Some(cell) = self.get_mut(x, y) {
+                    cell.background = background.over(cell.background);
+                }
+            }
+        }
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) {
+        self.cursor = Some((x, y));
+    }
+
+    fn render(&mut self, widget: &mut dyn Widget, area: Area, style: &Style) {
+        widget.render(self, area, style);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reset_buffers_are_empty() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 3,
+            rows: 2,
+        };
+        let mut buffer = CellBuffer::empty(area);
+        buffer.put_str(0, 0, "abc", RGBA::rgb(255, 0, 0), RGBA::rgb(0, 0, 255));
+        buffer.set_cursor(1, 1);
+        buffer.reset(area);
+        assert_eq!(buffer, CellBuffer::empty(area));
+
+        let larger = Area { cols: 4, ..area };
+        buffer.reset(larger);
+        assert_eq!(buffer, CellBuffer::empty(larger));
+    }
+
+    #[test]
+    fn cells_are_addressed_in_absolute_coordinates() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 2,
+            y: 1,
+            cols: 3,
+            rows: 2,
+        });
+        buffer.get_mut(4, 2).unwrap().symbol = 'x';
+        buffer.get_mut(2, 1).unwrap().symbol = 'y';
+
+        assert_eq!(buffer.get(4, 2).unwrap().symbol, 'x');
+        assert!(buffer.get(5, 2).is_none());
+        assert!(buffer.get(1, 1).is_none());
+        assert_eq!(buffer.to_text(), "y  \n  x");
+    }
+
+    #[test]
+    fn crop() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 3,
+            rows: 3,
+        });
+        buffer.get_mut(1, 1).unwrap().symbol = 'x';
+        buffer.get_mut(2, 2).unwrap().symbol = 'y';
+
+        let cropped = buffer.crop(Area {
+            x: 1,
+            y: 1,
+            cols: 3,
+            rows: 2,
+        });
+        assert_eq!(cropped.get(1, 1).unwrap().symbol, 'x');
+        assert!(cropped.get(0, 0).is_none());
+        assert_eq!(cropped.to_text(), "x  \n y ");
+    }
+
+    #[test]
+    fn put_str_is_clipped_to_the_buffer() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 4,
+            rows: 1,
+        });
+        buffer.put_str(2, 0, "abc", RGBA::rgb(255, 0, 0), RGBA::transparent());
+        buffer.put_str(0, 1, "below", RGBA::rgb(255, 0, 0), RGBA::transparent());
+
+        assert_eq!(buffer.to_text(), "  ab");
+        assert_eq!(buffer.get(2, 0).unwrap().foreground, RGBA::rgb(255, 0, 0));
+        assert_eq!(buffer.get(2, 0).unwrap().background, RGBA::transparent());
+    }
+
+    #[test]
+    fn cursor_is_hidden_until_placed() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 4,
+            rows: 2,
+        });
+        assert_eq!(buffer.cursor(), None);
+
+        buffer.set_cursor(3, 1);
+        assert_eq!(buffer.cursor(), Some((3, 1)));
+    }
+
+    #[test]
+    fn fill_rect_keeps_the_characters() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 3,
+            rows: 1,
+        });
+        buffer.put_str(0, 0, "abc", RGBA::transparent(), RGBA::transparent());
+        buffer.fill_rect(
+            Area {
+                x: 1,
+                y: 0,
+                cols: 5,
+                rows: 5,
+            },
+            RGBA::rgb(0, 0, 255),
+        );
+
+        assert_eq!(buffer.to_text(), "abc");
+        assert_eq!(buffer.get(0, 0).unwrap().background, RGBA::transparent());
+        assert_eq!(buffer.get(2, 0).unwrap().background, RGBA::rgb(0, 0, 255));
+    }
+
+    #[test]
+    fn colors_are_blended_over_what_is_below() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 2,
+            rows: 1,
+        };
+        let mut buffer = CellBuffer::empty(area);
+        buffer.fill_rect(area, RGBA::rgb(0, 0, 200));
+        buffer.fill_rect(area, RGBA::from(255, 0, 0, 128));
+        assert_eq!(buffer.get(0, 0).unwrap().background, RGBA::rgb(128, 0, 100));
+
+        // Half transparent white text fades into its background
+        buffer.put_str(0, 0, "a", RGBA::from(255, 255, 255, 128), RGBA::transparent());
+        assert_eq!(buffer.get(0, 0).unwrap().foreground, RGBA::rgb(192, 128, 178));
+        // A transparent foreground keeps the color of the text drawn before
+        buffer.put_str(0, 0, "b", RGBA::transparent(), RGBA::transparent());
+        assert_eq!(buffer.get(0, 0).unwrap().foreground, RGBA::rgb(192, 128, 178));
+    }
+}
@@ -0,0 +1,141 @@
+use std::time::{Duration, Instant};
+
+/**
+ * Decides how long the main loop may block waiting for input and whether a frame should be drawn.
+ *
+ * A frame is drawn when a redraw was requested (an event arrived, a view asked for it) or a tick
+ * fired, but never more often than the configured max FPS allows.
+ */
+pub struct FrameClock {
+    tick_rate: Duration,
+    frame_interval: Duration,
+    last_tick: Instant,
+    last_frame: Option<Instant>,
+    redraw_pending: bool,
+}
+
+impl FrameClock {
+    /**
+     * A `max_fps` of 0 leaves the frame rate unlimited
+     */
+    pub fn from(tick_rate: Duration, max_fps: u32, now: Instant) -> Self {
+        let frame_interval = if max_fps == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(1) / max_fps
+        };
+
+        Self {
+            tick_rate,
+            frame_interval,
+            last_tick: now,
+            last_frame: None,
+            redraw_pending: true,
+        }
+    }
+
+    pub fn request_redraw(&mut self) {
+        self.redraw_pending = true;
+    }
+
+    /**
+     * How long the loop may wait for input before something needs to happen
+     */
+    pub fn timeout(&self, now: Instant) -> Duration {
+        let until_tick = (self.last_tick + self.tick_rate).saturating_duration_since(now);
+        if !self.redraw_pending {
+            return until_tick;
+        }
+
+        let until_frame = match self.last_frame {
+            Some(last_frame) => (last_frame + self.frame_interval).saturating_duration_since(now),
+            None => Duration::ZERO,
+        };
+        until_tick.min(until_frame)
+    }
+
+    /**
+     * Fire the tick if it is due and report whether a frame should be drawn now.
+     * Returning true consumes the pending redraw.
+     */
+    pub fn should_render(&mut self, now: Instant) -> bool {
+        if now >= self.last_tick + self.tick_rate {
+            self.last_tick = now;
+            self.redraw_pending = true;
+        }
+
+        if !self.redraw_pending {
+            return false;
+        }
+        if let Some(last_frame) = self.last_frame {
+            if now < last_frame + self.frame_interval {
+                return false;
+            }
+        }
+
+        self.redraw_pending = false;
+        self.last_frame = Some(now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TICK: Duration = Duration::from_millis(250);
+
+    #[test]
+    fn first_frame_is_drawn_immediately() {
+        let start = Instant::now();
+        let mut clock = FrameClock::from(TICK, 60, start);
+
+        assert_eq!(clock.timeout(start), Duration::ZERO);
+        assert!(clock.should_render(start));
+        assert!(!clock.should_render(start));
+    }
+
+    #[test]
+    fn idle_waits_for_the_tick() {
+        let start = Instant::now();
+        let mut clock = FrameClock::from(TICK, 60, start);
+        assert!(clock.should_render(start));
+
+        let later = start + Duration::from_millis(100);
+        assert_eq!(clock.timeout(later), Duration::from_millis(150));
+        assert!(!clock.should_render(later));
+
+        let tick = start + TICK;
+        assert_eq!(clock.timeout(tick), Duration::ZERO);
+        assert!(clock.should_render(tick));
+        assert_eq!(clock.timeout(tick), TICK);
+    }
+
+    #[test]
+    fn redraw_requests_are_rate_limited() {
+        let start = Instant::now();
+        let mut clock = FrameClock::from(TICK, 10, start);
+        assert!(clock.should_render(start));
+
+        let soon = start + Duration::from_millis(40);
+        clock.request_redraw();
+        assert_eq!(clock.timeout(soon), Duration::from_millis(60));
+        assert!(!clock.should_render(soon));
+
+        // The request is not lost, it is served once the frame interval has elapsed
+        let frame = start + Duration::from_millis(100);
+        assert!(clock.should_render(frame));
+        assert!(!clock.should_render(frame));
+    }
+
+    #[test]
+    fn unlimited_frame_rate() {
+        let start = Instant::now();
+        let mut clock = FrameClock::from(TICK, 0, start);
+        assert!(clock.should_render(start));
+
+        clock.request_redraw();
+        assert_eq!(clock.timeout(start), Duration::ZERO);
+        assert!(clock.should_render(start));
+    }
+}
@@ -0,0 +1,545 @@
+use std::{
+    collections::BTreeMap,
+    env, error,
+    ffi::OsString,
+    fmt, fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use super::{
+    event::{Key, KeyEvent, Modifiers},
+    palette::{self, ColorDepth},
+    ui::{Role, Theme, RGBA, THEME_NAMES},
+    view,
+};
+
+mod watcher;
+
+pub use watcher::ConfigWatcher;
+
+/**
+ * Location of the config file inside a config directory
+ */
+const FILE_NAME: &str = "visualnv/config.toml";
+const DEFAULT_VIEW: &str = "home";
+const DEFAULT_THEME: &str = "dark";
+const DEFAULT_TICK_RATE: Duration = Duration::from_millis(250);
+const DEFAULT_MAX_FPS: u32 = 60;
+
+/**
+ * User settings. Everything missing from the config file keeps its default.
+ *
+ * ```toml
+ * view = "home"
+ * theme = "dark"
+ * tick_rate_ms = 250
+ * max_fps = 60
+ * # One of truecolor, 256, 16 or none, detected from the environment when left out
+ * color_depth = "256"
+ *
+ * [keys]
+ * quit = "ctrl+c"
+ * export = "ctrl+s"
+ *
+ * # Every role left out keeps the color of the base theme, "dark" unless given
+ * [themes.mine]
+ * base = "light"
+ * accent = "#ff8800"
+ * # Colors are #rrggbb, #rrggbbaa or CSS names, see `RGBA::parse`
+ * selection = "#61afef80"
+ * ```
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct Config {
+    /**
+     * View the app starts on, one of `view::NAMES`
+     */
+    pub view: String,
+    /**
+     * Name of a built-in theme or one of `themes`, the dark theme when unset
+     */
+    pub theme: Option<String>,
+    /**
+     * Themes defined in the config file, they hide built-in themes of the same name
+     */
+    pub themes: BTreeMap<String, Theme>,
+    /**
+     * How often a frame is drawn when no input arrives
+     */
+    pub tick_rate: Duration,
+    /**
+     * 0 leaves the frame rate unlimited
+     */
+    pub max_fps: u32,
+    /**
+     * Overrides the color depth detected from the environment, see `get_color_depth`. Only read
+     * at startup.
+     */
+    pub color_depth: Option<ColorDepth>,
+    pub keys: KeyBindings,
+}
+
+/**
+ * Keys handled by the app itself, before the view on screen sees them
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct KeyBindings {
+    pub quit: KeyEvent,
+    /**
+     * Save the frame on screen as HTML and SVG
+     */
+    pub export: KeyEvent,
+}
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        Self {
+            quit: KeyEvent::from(Key::Char('c')).with_modifiers(Modifiers::CTRL),
+            export: KeyEvent::from(Key::Char('s')).with_modifiers(Modifiers::CTRL),
+        }
+    }
+}
+
+impl Config {
+    pub fn new() -> Self {
+        Self {
+            view: String::from(DEFAULT_VIEW),
+            theme: None,
+            themes: BTreeMap::new(),
+            tick_rate: DEFAULT_TICK_RATE,
+            max_fps: DEFAULT_MAX_FPS,
+            color_depth: None,
+            keys: KeyBindings::new(),
+        }
+    }
+
+    /**
+     * The first config file found in `$XDG_CONFIG_HOME` (`~/.config` when unset), then in
+     * `$XDG_CONFIG_DIRS` (`/etc/xdg` when unset)
+     */
+    pub fn find() -> Option<PathBuf> {
+        search_path(
+            env::var_os("XDG_CONFIG_HOME"),
+            env::var_os("HOME"),
+            env::var_os("XDG_CONFIG_DIRS"),
+        )
+        .into_iter()
+        .find(|path| path.is_file())
+    }
+
+    /**
+     * The theme `theme` names, or why there is none
+     */
+    pub fn get_theme(&self) -> Result<Theme, String> {
+        let name = self.theme.as_deref().unwrap_or(DEFAULT_THEME);
+        self.themes
+            .get(name)
+            .copied()
+            .or_else(|| Theme::by_name(name))
+            .ok_or_else(|| {
+                let mut names: Vec<&str> = THEME_NAMES.to_vec();
+                let user_themes = self.themes.keys().map(String::as_str);
+                names.extend(user_themes.filter(|name| !THEME_NAMES.contains(name)));
+                format!("unknown theme {name:?}, expected one of {}", names.join(", "))
+            })
+    }
+
+    /**
+     * The configured color depth, or the one the environment suggests
+     */
+    pub fn get_color_depth(&self) -> ColorDepth {
+        self.color_depth.unwrap_or_else(ColorDepth::detect)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path).map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Self::parse(&text, path)
+    }
+
+    /**
+     * `path` is only used to point at the error
+     */
+    pub fn parse(text: &str, path: &Path) -> Result<Self, Error> {
+        Self::from_toml(text).map_err(|invalid| {
+            let (line, col) = invalid
+                .span
+                .map(|span| position(text, span.start))
+                .unwrap_or((1, 1));
+            Error::Invalid {
+                path: path.to_path_buf(),
+                line,
+                col,
+                message: invalid.message,
+            }
+        })
+    }
+
+    fn from_toml(text: &str) -> Result<Self, Invalid> {
+        let file: File = toml::from_str(text).map_err(|err| Invalid {
+            span: err.span(),
+            message: String::from(err.message().trim_end()),
+        })?;
+
+        let mut config = Self::new();
+        if let Some(view) = file.view {
+            if !view::NAMES.contains(&view.get_ref().as_str()) {
+                return Err(Invalid::at(
+                    &view,
+                    format!("unknown view, expected one of {}", view::NAMES.join(", ")),
+                ));
+            }
+            config.view = view.into_inner();
+        }
+        for (name, table) in file.themes {
+            config.themes.insert(name, parse_theme(table)?);
+        }
+        if let Some(theme) = file.theme {
+            config.theme = Some(theme.get_ref().clone());
+            config.get_theme().map_err(|message| Invalid::at(&theme, message))?;
+        }
+        if let Some(tick_rate) = file.tick_rate_ms {
+            if *tick_rate.get_ref() == 0 {
+                return Err(Invalid::at(&tick_rate, String::from("the tick rate must be at least 1 ms")));
+            }
+            config.tick_rate = Duration::from_millis(tick_rate.into_inner());
+        }
+        if let Some(max_fps) = file.max_fps {
+            config.max_fps = max_fps;
+        }
+        if let Some(color_depth) = file.color_depth {
+            let parsed = ColorDepth::parse(color_depth.get_ref()).ok_or_else(|| {
+                Invalid::at(
+                    &color_depth,
+                    format!("unknown color depth, expected one of {}", palette::NAMES.join(", ")),
+                )
+            })?;
+            config.color_depth = Some(parsed);
+        }
+        if let Some(quit) = file.keys.quit {
+            config.keys.quit = parse_key(&quit)?;
+        }
+        if let Some(export) = file.keys.export {
+            config.keys.export = parse_key(&export)?;
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /**
+     * The file is not valid TOML or holds a setting that makes no sense, `line` and `col` start
+     * at 1
+     */
+    Invalid {
+        path: PathBuf,
+        line: usize,
+        col: usize,
+        message: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, .. } => write!(f, "cannot read the config file {}", path.display()),
+            Self::Invalid {
+                path,
+                line,
+                col,
+                message,
+            } => write!(f, "{}:{line}:{col}: {message}", path.display()),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            Self::Invalid { .. } => None,
+        }
+    }
+}
+
+/**
+ * Layout of the config file, checked and turned into a `Config` by `Config::from_toml`
+ */
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    view: Option<Spanned<String>>,
+    theme: Option<Spanned<String>>,
+    tick_rate_ms: Option<Spanned<u64>>,
+    max_fps: Option<u32>,
+    color_depth: Option<Spanned<String>>,
+    #[serde(default)]
+    keys: Keys,
+    /**
+     * Roles by name, plus `base`
+     */
+    #[serde(default)]
+    themes: BTreeMap<String, BTreeMap<String, Spanned<String>>>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct Keys {
+    quit: Option<Spanned<String>>,
+    export: Option<Spanned<String>>,
+}
+
+/**
+ * What is wrong with the file and where, as a byte range
+ */
+struct Invalid {
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+impl Invalid {
+    fn at<T>(value: &Spanned<T>, message: String) -> Self {
+        Self {
+            span: Some(value.span()),
+            message,
+        }
+    }
+}
+
+fn parse_key(binding: &Spanned<String>) -> Result<KeyEvent, Invalid> {
+    KeyEvent::parse(binding.get_ref()).ok_or_else(|| {
+        Invalid::at(
+            binding,
+            format!("unknown key {:?}, expected something like \"ctrl+s\"", binding.get_ref()),
+        )
+    })
+}
+
+fn parse_theme(mut table: BTreeMap<String, Spanned<String>>) -> Result<Theme, Invalid> {
+    let mut theme = Theme::dark();
+    if let Some(base) = table.remove("base") {
+        theme = Theme::by_name(base.get_ref()).ok_or_else(|| {
+            Invalid::at(
+                &base,
+                format!("unknown base theme, expected one of {}", THEME_NAMES.join(", ")),
+            )
+        })?;
+    }
+    for (name, color) in table {
+        let Some(role) = Role::ALL.into_iter().find(|role| role.name() == name) else {
+            let roles: Vec<&str> = Role::ALL.iter().map(Role::name).collect();
+            return Err(Invalid::at(
+                &color,
+                format!("unknown color role {name:?}, expected base or one of {}", roles.join(", ")),
+            ));
+        };
+        let rgba = RGBA::parse(color.get_ref()).ok_or_else(|| {
+            Invalid::at(
+                &color,
+                format!(
+                    "invalid color {:?}, expected #rrggbb, #rrggbbaa or a name like \"orange\"",
+                    color.get_ref()
+                ),
+            )
+        })?;
+        theme.set(role, rgba);
+    }
+    Ok(theme)
+}
+
+/**
+ * 1-based line and column of the byte at `offset`
+ */
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/**
+ * Candidate config files, most important first. Unset, empty and relative directories are skipped
+ * as the XDG spec asks.
+ */
+fn search_path(
+    config_home: Option<OsString>,
+    home: Option<OsString>,
+    config_dirs: Option<OsString>,
+) -> Vec<PathBuf> {
+    let absolute = |dir: PathBuf| dir.is_absolute().then_some(dir);
+
+    let config_home = config_home
+        .map(PathBuf::from)
+        .and_then(absolute)
+        .or_else(|| home.map(|home| PathBuf::from(home).join(".config")).and_then(absolute));
+    let config_dirs: Vec<PathBuf> = match config_dirs.filter(|dirs| !dirs.is_empty()) {
+        Some(dirs) => env::split_paths(&dirs).filter_map(absolute).collect(),
+        None => vec![PathBuf::from("/etc/xdg")],
+    };
+
+    config_home
+        .into_iter()
+        .chain(config_dirs)
+        .map(|dir| dir.join(FILE_NAME))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Config, Error> {
+        Config::parse(text, Path::new("config.toml"))
+    }
+
+    fn error_message(text: &str) -> String {
+        parse(text).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn empty_file_keeps_the_defaults() {
+        assert_eq!(parse("").unwrap(), Config::new());
+    }
+
+    #[test]
+    fn every_setting() {
+        let config = parse(
+            "view = \"cell\"\ntheme = \"light\"\ntick_rate_ms = 100\nmax_fps = 0\ncolor_depth = \"16\"\n\n\
+             [keys]\nquit = \"q\"\nexport = \"f2\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.view, "cell");
+        assert_eq!(config.theme.as_deref(), Some("light"));
+        assert_eq!(config.tick_rate, Duration::from_millis(100));
+        assert_eq!(config.max_fps, 0);
+        assert_eq!(config.color_depth, Some(ColorDepth::Ansi16));
+        assert_eq!(config.keys.quit, KeyEvent::from(Key::Char('q')));
+        assert_eq!(config.keys.export, KeyEvent::from(Key::F(2)));
+    }
+
+    #[test]
+    fn partial_key_bindings() {
+        let config = parse("[keys]\nexport = \"alt+e\"\n").unwrap();
+
+        assert_eq!(config.keys.quit, KeyBindings::new().quit);
+        assert_eq!(
+            config.keys.export,
+            KeyEvent::from(Key::Char('e')).with_modifiers(Modifiers::ALT)
+        );
+    }
+
+    #[test]
+    fn syntax_errors_point_at_the_line_and_column() {
+        assert!(error_message("view = \"home\"\nmax_fps = \n").starts_with("config.toml:2:11: "));
+    }
+
+    #[test]
+    fn invalid_settings_point_at_the_value() {
+        assert_eq!(
+            error_message("max_fps = 30\nview = \"nowhere\"\n"),
+            "config.toml:2:8: unknown view, expected one of home, cell"
+        );
+        assert_eq!(
+            error_message("[keys]\n  quit = \"hyper+q\"\n"),
+            "config.toml:2:10: unknown key \"hyper+q\", expected something like \"ctrl+s\""
+        );
+        assert_eq!(
+            error_message("color_depth = 256"),
+            "config.toml:1:15: invalid type: integer `256`, expected a string"
+        );
+        assert_eq!(
+            error_message("color_depth = \"8\""),
+            "config.toml:1:15: unknown color depth, expected one of truecolor, 256, 16, none"
+        );
+        assert_eq!(
+            error_message("tick_rate_ms = 0"),
+            "config.toml:1:16: the tick rate must be at least 1 ms"
+        );
+        assert!(error_message("max_fps = \"fast\"").starts_with("config.toml:1:11: "));
+        assert!(error_message("colour = \"red\"").starts_with("config.toml:1:1: "));
+    }
+
+    #[test]
+    fn user_themes() {
+        let config = parse(
+            "theme = \"mine\"\n\n[themes.mine]\nbase = \"light\"\naccent = \"#ff8800\"\nerror = \"purple\"\n\
+             selection = \"#0000ff40\"\n\n\
+             [themes.light]\ntext = \"#000000\"\n",
+        )
+        .unwrap();
+        let light = Theme::by_name("light").unwrap();
+
+        let mine = config.get_theme().unwrap();
+        assert_eq!(mine.accent, RGBA::rgb(255, 136, 0));
+        assert_eq!(mine.text, light.text);
+        assert_eq!(mine.error, RGBA::rgb(128, 0, 128));
+        assert_eq!(mine.selection, RGBA::from(0, 0, 255, 64));
+        // User themes hide the built-in ones
+        assert_eq!(config.themes["light"].text, RGBA::rgb(0, 0, 0));
+        assert_eq!(config.themes["light"].accent, Theme::dark().accent);
+    }
+
+    #[test]
+    fn invalid_themes_point_at_the_value() {
+        assert_eq!(
+            error_message("theme = \"solarized\""),
+            "config.toml:1:9: unknown theme \"solarized\", expected one of dark, light, high-contrast"
+        );
+        assert_eq!(
+            error_message("[themes.mine]\nbase = \"mine\""),
+            "config.toml:2:8: unknown base theme, expected one of dark, light, high-contrast"
+        );
+        assert!(error_message("[themes.mine]\ncolour = \"#000000\"")
+            .starts_with("config.toml:2:10: unknown color role \"colour\""));
+        assert_eq!(
+            error_message("[themes.mine]\ntext = \"dark red\""),
+            "config.toml:2:8: invalid color \"dark red\", expected #rrggbb, #rrggbbaa or a name like \"orange\""
+        );
+    }
+
+    #[test]
+    fn missing_file() {
+        let err = Config::load(Path::new("/nonexistent/visualnv.toml")).unwrap_err();
+
+        assert_eq!(err.to_string(), "cannot read the config file /nonexistent/visualnv.toml");
+        assert!(error::Error::source(&err).is_some());
+    }
+
+    #[test]
+    fn xdg_search_path() {
+        let os = |text: &str| Some(OsString::from(text));
+
+        assert_eq!(
+            search_path(os("/config"), os("/home/me"), os("/etc/a:relative:/etc/b")),
+            vec![
+                PathBuf::from("/config/visualnv/config.toml"),
+                PathBuf::from("/etc/a/visualnv/config.toml"),
+                PathBuf::from("/etc/b/visualnv/config.toml"),
+            ]
+        );
+        assert_eq!(
+            search_path(os("relative"), os("/home/me"), os("")),
+            vec![
+                PathBuf::from("/home/me/.config/visualnv/config.toml"),
+                PathBuf::from("/etc/xdg/visualnv/config.toml"),
+            ]
+        );
+        assert_eq!(
+            search_path(None, None, None),
+            vec![PathBuf::from("/etc/xdg/visualnv/config.toml")]
+        );
+    }
+}
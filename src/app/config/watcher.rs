@@ -0,0 +1,113 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{Config, Error};
+
+/**
+ * Notices changes to the config file by polling its modification time.
+ *
+ * Editors often save by replacing the file, so a file that is missing for a moment is not an
+ * error: nothing is reported until it shows up again.
+ */
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    interval: Duration,
+    next_check: Instant,
+    adjust: Box<dyn Fn(&mut Config)>,
+}
+
+impl ConfigWatcher {
+    /**
+     * The file as it is now counts as loaded, only later changes are reported
+     */
+    pub fn from(path: PathBuf, interval: Duration, now: Instant) -> Self {
+        Self {
+            modified: modified(&path),
+            path,
+            interval,
+            next_check: now + interval,
+            adjust: Box::new(|_| {}),
+        }
+    }
+
+    /**
+     * Change every reloaded config before it is handed out, e.g. to keep command line overrides
+     */
+    pub fn with_adjustment(mut self, adjust: impl Fn(&mut Config) + 'static) -> Self {
+        self.adjust = Box::new(adjust);
+        self
+    }
+
+    /**
+     * The reloaded config if the file changed since it was last seen. The file system is looked
+     * at no more often than the interval allows.
+     */
+    pub fn poll(&mut self, now: Instant) -> Option<Result<Config, Error>> {
+        if now < self.next_check {
+            return None;
+        }
+        self.next_check = now + self.interval;
+
+        let modified = modified(&self.path)?;
+        if self.modified == Some(modified) {
+            return None;
+        }
+        self.modified = Some(modified);
+
+        let result = Config::load(&self.path).map(|mut config| {
+            (self.adjust)(&mut config);
+            config
+        });
+        Some(result)
+    }
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn write(path: &PathBuf, text: &str, seconds: u64) {
+        fs::write(path, text).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(seconds);
+        File::options().write(true).open(path).unwrap().set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn changes_are_reloaded() {
+        let path = std::env::temp_dir().join(format!("visualnv-watch-{}.toml", std::process::id()));
+        write(&path, "max_fps = 10", 1);
+        let start = Instant::now();
+        let mut watcher = ConfigWatcher::from(path.clone(), Duration::from_secs(1), start)
+            .with_adjustment(|config| config.view = String::from("cell"));
+
+        // Unchanged file, and a change is only noticed once the interval is over
+        assert!(watcher.poll(start + Duration::from_secs(1)).is_none());
+        write(&path, "max_fps = 20", 2);
+        assert!(watcher.poll(start + Duration::from_millis(1500)).is_none());
+        let config = watcher.poll(start + Duration::from_secs(2)).unwrap().unwrap();
+        assert_eq!(config.max_fps, 20);
+        assert_eq!(config.view, "cell");
+
+        write(&path, "max_fps = ", 3);
+        let reloaded = watcher.poll(start + Duration::from_secs(3));
+        assert!(matches!(reloaded, Some(Err(Error::Invalid { line: 1, .. }))));
+
+        // A missing file is waited for
+        fs::remove_file(&path).unwrap();
+        assert!(watcher.poll(start + Duration::from_secs(4)).is_none());
+        write(&path, "max_fps = 40", 5);
+        let config = watcher.poll(start + Duration::from_secs(5)).unwrap().unwrap();
+        assert_eq!(config.max_fps, 40);
+        fs::remove_file(&path).unwrap();
+    }
+}
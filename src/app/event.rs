@@ -1,20 +1,241 @@
-pub struct Event {
+use std::ops::{BitOr, BitOrAssign};
+
+pub enum Event {
+    Key(KeyEvent),
+    Mouse(MouseEvent),
+    /**
+     * The terminal now measures `cols` x `rows` cells
+     */
+    Resize { cols: u16, rows: u16 },
+    FocusGained,
+    FocusLost,
+    /**
+     * Text pasted in one go while bracketed paste is enabled
+     */
+    Paste(String),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct KeyEvent {
     key: Key,
+    modifiers: Modifiers,
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub enum Key {
-    ESC,
-    ENTER,
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Delete,
+    Insert,
+    Tab,
+    BackTab,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    F(u8),
+    /**
+     * Keys the backend reports but which have no meaning for views (media keys, lock keys, ...)
+     */
+    Unknown,
 }
 
-impl Event {
+/**
+ * Bitset of the modifier keys held while a key was pressed
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub struct Modifiers {
+    bits: u8,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self { bits: 0 };
+    pub const SHIFT: Self = Self { bits: 1 };
+    pub const CTRL: Self = Self { bits: 1 << 1 };
+    pub const ALT: Self = Self { bits: 1 << 2 };
+    pub const SUPER: Self = Self { bits: 1 << 3 };
+
+    // Part of reading a key, though no view checks its modifiers yet
+    #[allow(dead_code)]
+    pub fn contains(&self, other: Self) -> bool {
+        self.bits & other.bits == other.bits
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.bits |= rhs.bits;
+    }
+}
+
+impl KeyEvent {
     pub fn from(key: Key) -> Self {
         Self {
             key,
+            modifiers: Modifiers::NONE,
         }
     }
 
+    pub fn with_modifiers(mut self, modifiers: Modifiers) -> Self {
+        self.modifiers = modifiers;
+        self
+    }
+
     pub fn get_key(&self) -> &Key {
         &self.key
     }
+
+    #[allow(dead_code)]
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /**
+     * Key binding written like `ctrl+s`, `alt+shift+left` or `f5`: modifiers joined with `+`
+     * followed by a single character or a key name, case insensitive except for characters.
+     * `None` when the text names no key.
+     */
+    pub fn parse(text: &str) -> Option<Self> {
+        let (modifier_names, key_name) = match text.rsplit_once('+') {
+            // A trailing `+` is the plus key itself
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').unwrap_or(modifiers), "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", text),
+        };
+
+        let mut modifiers = Modifiers::NONE;
+        for name in modifier_names.split('+').filter(|name| !name.is_empty()) {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "shift" => Modifiers::SHIFT,
+                "ctrl" | "control" => Modifiers::CTRL,
+                "alt" => Modifiers::ALT,
+                "super" => Modifiers::SUPER,
+                _ => return None,
+            };
+        }
+
+        let mut chars = key_name.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) => Key::Char(c),
+            _ => match key_name.to_ascii_lowercase().as_str() {
+                "enter" => Key::Enter,
+                "esc" | "escape" => Key::Esc,
+                "backspace" => Key::Backspace,
+                "delete" => Key::Delete,
+                "insert" => Key::Insert,
+                "tab" => Key::Tab,
+                "backtab" => Key::BackTab,
+                "left" => Key::Left,
+                "right" => Key::Right,
+                "up" => Key::Up,
+                "down" => Key::Down,
+                "home" => Key::Home,
+                "end" => Key::End,
+                "pageup" => Key::PageUp,
+                "pagedown" => Key::PageDown,
+                "space" => Key::Char(' '),
+                name => Key::F(name.strip_prefix('f')?.parse().ok().filter(|n| (1..=24).contains(n))?),
+            },
+        };
+        Some(Self::from(key).with_modifiers(modifiers))
+    }
+}
+
+/**
+ * A mouse action at a cell position, where (0, 0) is the top left cell of the screen
+ */
+pub struct MouseEvent {
+    kind: MouseKind,
+    col: u16,
+    row: u16,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MouseKind {
+    Press(MouseButton),
+    Release(MouseButton),
+    Drag(MouseButton),
+    Moved,
+    ScrollUp,
+    ScrollDown,
+    ScrollLeft,
+    ScrollRight,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MouseButton {
+    Left,
+    Right,
+    Middle,
+}
+
+impl MouseEvent {
+    pub fn from(kind: MouseKind, col: u16, row: u16) -> Self {
+        Self {
+            kind,
+            col,
+            row,
+        }
+    }
+
+    pub fn get_kind(&self) -> &MouseKind {
+        &self.kind
+    }
+
+    pub fn get_col(&self) -> u16 {
+        self.col
+    }
+
+    pub fn get_row(&self) -> u16 {
+        self.row
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_key_bindings() {
+        assert_eq!(
+            KeyEvent::parse("ctrl+s"),
+            Some(KeyEvent::from(Key::Char('s')).with_modifiers(Modifiers::CTRL))
+        );
+        assert_eq!(
+            KeyEvent::parse("Alt+Shift+Left"),
+            Some(KeyEvent::from(Key::Left).with_modifiers(Modifiers::ALT | Modifiers::SHIFT))
+        );
+        assert_eq!(KeyEvent::parse("f12"), Some(KeyEvent::from(Key::F(12))));
+        assert_eq!(KeyEvent::parse("Q"), Some(KeyEvent::from(Key::Char('Q'))));
+        assert_eq!(KeyEvent::parse("+"), Some(KeyEvent::from(Key::Char('+'))));
+        assert_eq!(
+            KeyEvent::parse("ctrl++"),
+            Some(KeyEvent::from(Key::Char('+')).with_modifiers(Modifiers::CTRL))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_keys() {
+        assert_eq!(KeyEvent::parse(""), None);
+        assert_eq!(KeyEvent::parse("hyper+x"), None);
+        assert_eq!(KeyEvent::parse("ctrl+nothing"), None);
+        assert_eq!(KeyEvent::parse("f0"), None);
+        assert_eq!(KeyEvent::parse("f99"), None);
+    }
 }
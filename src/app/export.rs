@@ -0,0 +1,240 @@
+use std::fmt::Write;
+
+use super::{buffer::CellBuffer, ui::RGBA};
+
+/**
+ * Colors standing in for the terminal's own defaults, which are unknown outside of it
+ */
+const DEFAULT_FOREGROUND: RGBA = RGBA::rgb(208, 208, 208);
+const DEFAULT_BACKGROUND: RGBA = RGBA::rgb(0, 0, 0);
+
+const FONT_SIZE: f32 = 14.0;
+const CELL_WIDTH: f32 = 8.4;
+const CELL_HEIGHT: f32 = 17.0;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Format {
+    /**
+     * Characters only, trailing blanks trimmed from every row
+     */
+    Text,
+    Html,
+    Svg,
+}
+
+impl Format {
+    pub fn export(&self, buffer: &CellBuffer) -> String {
+        match self {
+            Self::Text => to_text(buffer),
+            Self::Html => to_html(buffer),
+            Self::Svg => to_svg(buffer),
+        }
+    }
+}
+
+pub fn to_text(buffer: &CellBuffer) -> String {
+    let area = buffer.area();
+    let mut text = String::new();
+    for y in area.y..area.y + area.rows {
+        text.push_str(buffer.row_text(y).trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+/**
+ * Standalone page showing the frame in a `<pre>` block, one span per run of cells sharing colors
+ */
+pub fn to_html(buffer: &CellBuffer) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>visualnv</title>\n</head>\n\
+         <body style=\"margin: 0; background: {background}\">\n\
+         <pre style=\"margin: 0; font-family: monospace; font-size: {FONT_SIZE}px; \
+         line-height: {CELL_HEIGHT}px; color: {foreground}; background: {background}\">\n",
+        foreground = hex(DEFAULT_FOREGROUND),
+        background = hex(DEFAULT_BACKGROUND),
+    );
+    let area = buffer.area();
+    for y in area.y..area.y + area.rows {
+        for run in runs(buffer, y) {
+            let text = escape(&run.text);
+            if run.foreground.is_transparent() && run.background.is_transparent() {
+                html.push_str(&text);
+                continue;
+            }
+            html.push_str("<span style=\"");
+            if !run.foreground.is_transparent() {
+                let _ = write!(html, "color: {};", hex(run.foreground));
+            }
+            if !run.background.is_transparent() {
+                let _ = write!(html, "background: {};", hex(run.background));
+            }
+            let _ = write!(html, "\">{text}</span>");
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+/**
+ * Standalone image with one rectangle per run of cells sharing a background and one text element
+ * per run of characters sharing a foreground. Text is stretched to its cells so every font keeps
+ * the grid.
+ */
+pub fn to_svg(buffer: &CellBuffer) -> String {
+    let area = buffer.area();
+    let width = area.cols as f32 * CELL_WIDTH;
+    let height = area.rows as f32 * CELL_HEIGHT;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width:.1}\" height=\"{height:.1}\" \
+         viewBox=\"0 0 {width:.1} {height:.1}\" font-family=\"monospace\" font-size=\"{FONT_SIZE}\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        hex(DEFAULT_BACKGROUND)
+    );
+    for y in area.y..area.y + area.rows {
+        let top = (y - area.y) as f32 * CELL_HEIGHT;
+        for run in runs(buffer, y).iter().filter(|run| !run.background.is_transparent()) {
+            let _ = writeln!(
+                svg,
+                "<rect x=\"{:.1}\" y=\"{top:.1}\" width=\"{:.1}\" height=\"{CELL_HEIGHT:.1}\" fill=\"{}\"/>",
+                (run.x - area.x) as f32 * CELL_WIDTH,
+                run.cols as f32 * CELL_WIDTH,
+                hex(run.background)
+            );
+        }
+        // Text sits on its baseline, a little above the bottom of the cell
+        let baseline = top + CELL_HEIGHT * 0.8;
+        for run in runs(buffer, y).iter().filter(|run| !run.text.trim().is_empty()) {
+            let foreground = if run.foreground.is_transparent() {
+                DEFAULT_FOREGROUND
+            } else {
+                run.foreground
+            };
+            let _ = writeln!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{baseline:.1}\" textLength=\"{:.1}\" \
+                 lengthAdjust=\"spacingAndGlyphs\" fill=\"{}\" xml:space=\"preserve\">{}</text>",
+                (run.x - area.x) as f32 * CELL_WIDTH,
+                run.cols as f32 * CELL_WIDTH,
+                hex(foreground),
+                escape(&run.text)
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/**
+ * Cells next to each other on one row with the same colors
+ */
+struct Run {
+    x: u16,
+    cols: u16,
+    text: String,
+    foreground: RGBA,
+    background: RGBA,
+}
+
+fn runs(buffer: &CellBuffer, y: u16) -> Vec<Run> {
+    let area = buffer.area();
+    let mut runs: Vec<Run> = Vec::new();
+    for x in area.x..area.x + area.cols {
+        let cell = buffer.get(x, y).unwrap();
+        match runs.last_mut() {
+            Some(run) if run.foreground == cell.foreground && run.background == cell.background => {
+                run.cols += 1;
+                run.text.push(cell.symbol);
+            }
+            _ => runs.push(Run {
+                x,
+                cols: 1,
+                text: String::from(cell.symbol),
+                foreground: cell.foreground,
+                background: cell.background,
+            }),
+        }
+    }
+    runs
+}
+
+/**
+ * Colors are written without their alpha, what is left of it after compositing is the terminal's
+ * default showing through
+ */
+fn hex(color: RGBA) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+/**
+ * Escape the characters that would be read as markup, for both HTML and SVG
+ */
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::renderer::{Area, Renderer};
+
+    fn buffer() -> CellBuffer {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 6,
+            rows: 2,
+        });
+        buffer.put_str(0, 0, "a<b", RGBA::rgb(255, 0, 0), RGBA::transparent());
+        buffer.put_str(4, 0, "&", RGBA::transparent(), RGBA::transparent());
+        buffer.fill_rect(
+            Area {
+                x: 0,
+                y: 1,
+                cols: 2,
+                rows: 1,
+            },
+            RGBA::rgb(0, 0, 255),
+        );
+        buffer
+    }
+
+    #[test]
+    fn text() {
+        assert_eq!(to_text(&buffer()), "a<b &\n\n");
+    }
+
+    #[test]
+    fn html_runs_are_spans() {
+        let html = to_html(&buffer());
+
+        assert!(html.contains("<span style=\"color: #ff0000;\">a&lt;b</span> &amp; \n"));
+        assert!(html.contains("<span style=\"background: #0000ff;\">  </span>    \n"));
+        assert!(html.starts_with("<!DOCTYPE html>"));
+    }
+
+    #[test]
+    fn svg_draws_backgrounds_and_text() {
+        let svg = to_svg(&buffer());
+
+        assert!(svg.contains("width=\"50.4\" height=\"34.0\""));
+        assert!(svg.contains(
+            "<rect x=\"0.0\" y=\"17.0\" width=\"16.8\" height=\"17.0\" fill=\"#0000ff\"/>"
+        ));
+        assert!(svg.contains("textLength=\"25.2\" lengthAdjust=\"spacingAndGlyphs\" fill=\"#ff0000\" xml:space=\"preserve\">a&lt;b</text>"));
+        assert!(svg.contains("fill=\"#d0d0d0\" xml:space=\"preserve\"> &amp; </text>"));
+        // Blank runs draw nothing
+        assert_eq!(svg.matches("<text").count(), 2);
+    }
+}
@@ -0,0 +1,187 @@
+use std::{collections::VecDeque, time::Duration};
+
+use super::{
+    buffer::CellBuffer,
+    event::{Event, Key, KeyEvent, Modifiers},
+    renderer::{Area, Renderer},
+    screen::{Result, ScreenApi, ScreenOptions},
+    view::View,
+    App, Error,
+};
+
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+/**
+ * Screen drawing into memory instead of a terminal, fed by a scripted list of events.
+ *
+ * Every poll hands out the next scripted event without waiting. Once the script runs out the
+ * screen interrupts the app with Ctrl+C, like a user would, so `App::run` always returns.
+ */
+pub struct HeadlessScreen {
+    buffer: CellBuffer,
+    events: VecDeque<Event>,
+    frames: usize,
+}
+
+impl HeadlessScreen {
+    pub fn with_size(cols: u16, rows: u16) -> Self {
+        Self {
+            buffer: CellBuffer::empty(Area {
+                x: 0,
+                y: 0,
+                cols,
+                rows,
+            }),
+            events: VecDeque::new(),
+            frames: 0,
+        }
+    }
+
+    pub fn with_events(mut self, events: Vec<Event>) -> Self {
+        self.events.extend(events);
+        self
+    }
+
+    /**
+     * Script key presses without modifiers
+     */
+    pub fn with_keys(self, keys: &[Key]) -> Self {
+        let events = keys.iter().map(|key| Event::Key(KeyEvent::from(*key))).collect();
+        self.with_events(events)
+    }
+
+    /**
+     * Content of the last frame drawn
+     */
+    pub fn buffer(&self) -> CellBuffer {
+        self.buffer.clone()
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /**
+     * Run an app showing `view` on this screen until the script runs out. Nothing waits on a
+     * headless screen, so the frame rate is not limited and every event gets its frame.
+     */
+    pub fn run(self, view: Box<dyn View>) -> std::result::Result<Self, Error> {
+        let mut app = App::with_root_view(self, view).with_timing(Duration::from_secs(3600), 0);
+        app.run()?;
+        Ok(app.screen)
+    }
+}
+
+impl ScreenApi for HeadlessScreen {
+    fn new(_: &ScreenOptions) -> Result<Self> {
+        Ok(Self::with_size(DEFAULT_COLS, DEFAULT_ROWS))
+    }
+
+    fn poll_event(&mut self, _: Duration) -> Result<Option<Event>> {
+        let event = self.events.pop_front().unwrap_or_else(|| {
+            Event::Key(KeyEvent::from(Key::Char('c')).with_modifiers(Modifiers::CTRL))
+        });
+        if let Event::Resize { cols, rows } = event {
+            self.buffer = CellBuffer::empty(Area {
+                x: 0,
+                y: 0,
+                cols,
+                rows,
+            });
+        }
+        Ok(Some(event))
+    }
+
+    fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> Result<()> {
+        // Like a terminal backend every frame is drawn from scratch
+        self.buffer = CellBuffer::empty(self.buffer.area());
+        draw(&mut self.buffer);
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        ui::{Borders, Color, Label, Style, RGBA},
+        view::{self, Action},
+    };
+
+    /**
+     * Shows the characters typed so far
+     */
+    struct EchoView {
+        typed: String,
+    }
+
+    impl View for EchoView {
+        fn init(&mut self) -> std::result::Result<(), view::Error> {
+            Ok(())
+        }
+
+        fn update(&mut self, event: &Event) -> std::result::Result<Action, view::Error> {
+            if let Event::Key(key) = event {
+                if let Key::Char(c) = key.get_key() {
+                    self.typed.push(*c);
+                    return Ok(Action::Redraw);
+                }
+            }
+            Ok(Action::None)
+        }
+
+        fn render(&mut self, renderer: &mut dyn Renderer) -> std::result::Result<(), view::Error> {
+            let area = Area {
+                x: 1,
+                y: 1,
+                cols: 10,
+                rows: 1,
+            };
+            renderer.render(
+                &mut Label::from(self.typed.as_str()),
+                area,
+                &Style::from(Borders::none(), Color::Inherit, RGBA::rgb(255, 0, 0)),
+            );
+            Ok(())
+        }
+
+        fn close(&mut self) -> std::result::Result<(), view::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn scripted_keys_reach_the_view() {
+        let screen = HeadlessScreen::with_size(12, 3)
+            .with_keys(&[Key::Char('h'), Key::Char('i')]);
+        let view = Box::new(EchoView {
+            typed: String::new(),
+        });
+        let screen = screen.run(view).unwrap();
+
+        let buffer = screen.buffer();
+        assert_eq!(buffer.to_text(), "            \n hi         \n            ");
+        assert_eq!(buffer.get(1, 1).unwrap().foreground, RGBA::rgb(255, 0, 0));
+        assert_eq!(buffer.get(0, 1).unwrap().foreground, RGBA::transparent());
+    }
+
+    #[test]
+    fn resize_events_resize_the_buffer() {
+        let screen = HeadlessScreen::with_size(12, 3)
+            .with_events(vec![Event::Resize { cols: 20, rows: 4 }]);
+        let view = Box::new(EchoView {
+            typed: String::new(),
+        });
+        let screen = screen.run(view).unwrap();
+
+        let area = screen.buffer().area();
+        assert_eq!((area.cols, area.rows), (20, 4));
+        assert_eq!(screen.frames(), 2);
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    fmt::Display,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/**
+ * Appends timestamped lines to a log file. The terminal belongs to the app while it runs, so this
+ * is the only place to look at what happened. Without a file nothing is logged.
+ */
+pub struct Logger {
+    file: Option<File>,
+}
+
+impl Logger {
+    pub fn none() -> Self {
+        Self { file: None }
+    }
+
+    pub fn to_file(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file: Some(file) })
+    }
+
+    /**
+     * A log that cannot be written to must not take the app down, write errors are ignored
+     */
+    pub fn log(&mut self, message: impl Display) {
+        if let Some(file) = &mut self.file {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+            let _ = writeln!(file, "[{}.{:03}] {message}", now.as_secs(), now.subsec_millis());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn lines_are_appended() {
+        let path = std::env::temp_dir().join(format!("visualnv-log-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        Logger::to_file(&path).unwrap().log("first");
+        Logger::to_file(&path).unwrap().log(format_args!("second {}", 2));
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with('[') && lines[0].ends_with("] first"));
+        assert!(lines[1].ends_with("] second 2"));
+    }
+}
@@ -0,0 +1,275 @@
+use std::{env, ffi::OsString, fmt};
+
+use super::ui::RGBA;
+
+/**
+ * How many colors the terminal can show
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum ColorDepth {
+    /**
+     * Any 24-bit color
+     */
+    TrueColor,
+    /**
+     * The xterm palette: the 16 system colors, a 6x6x6 color cube and 24 grays
+     */
+    Ansi256,
+    /**
+     * The 8 system colors and their bright variants
+     */
+    Ansi16,
+    /**
+     * Only the terminal's own foreground and background
+     */
+    Monochrome,
+}
+
+/**
+ * Color as the terminal is told to draw it
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum TermColor {
+    Default,
+    Rgb(u8, u8, u8),
+    /**
+     * Entry of the 256 color palette
+     */
+    Indexed(u8),
+    /**
+     * One of the 16 system colors, 8 and up are the bright ones
+     */
+    Ansi(u8),
+}
+
+/**
+ * Names of the depths in the config file, see `ColorDepth::parse`
+ */
+pub const NAMES: [&str; 4] = ["truecolor", "256", "16", "none"];
+
+/**
+ * The system colors as xterm draws them. Terminals let users change them, so they are only a
+ * best guess.
+ */
+const SYSTEM_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/**
+ * Channel values of the 6x6x6 color cube at palette entries 16 to 231
+ */
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "truecolor" => Some(Self::TrueColor),
+            "256" => Some(Self::Ansi256),
+            "16" => Some(Self::Ansi16),
+            "none" => Some(Self::Monochrome),
+            _ => None,
+        }
+    }
+
+    /**
+     * Guess the depth from `NO_COLOR`, `COLORTERM` and `TERM`
+     */
+    pub fn detect() -> Self {
+        Self::from_env(
+            env::var_os("NO_COLOR"),
+            env::var_os("COLORTERM"),
+            env::var_os("TERM"),
+        )
+    }
+
+    fn from_env(no_color: Option<OsString>, colorterm: Option<OsString>, term: Option<OsString>) -> Self {
+        // https://no-color.org asks to honor any value but an empty one
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            return Self::Monochrome;
+        }
+        if let Some("truecolor" | "24bit") = colorterm.as_ref().and_then(|value| value.to_str()) {
+            return Self::TrueColor;
+        }
+        let term = term.as_ref().and_then(|value| value.to_str()).unwrap_or("");
+        if term == "dumb" {
+            Self::Monochrome
+        } else if term.ends_with("-direct") || term.ends_with("-truecolor") {
+            Self::TrueColor
+        } else if term.ends_with("256color") {
+            Self::Ansi256
+        } else {
+            Self::Ansi16
+        }
+    }
+
+    /**
+     * Nearest color the terminal can show. Transparent colors use the terminal's default, partly
+     * transparent ones are drawn as if they were opaque.
+     */
+    pub fn convert(&self, color: RGBA) -> TermColor {
+        if color.is_transparent() {
+            return TermColor::Default;
+        }
+        match self {
+            Self::TrueColor => TermColor::Rgb(color.red, color.green, color.blue),
+            Self::Ansi256 => TermColor::Indexed(to_256(color)),
+            Self::Ansi16 => TermColor::Ansi(to_16(color)),
+            Self::Monochrome => TermColor::Default,
+        }
+    }
+}
+
+impl fmt::Display for ColorDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::TrueColor => "truecolor",
+            Self::Ansi256 => "256",
+            Self::Ansi16 => "16",
+            Self::Monochrome => "none",
+        };
+        f.write_str(name)
+    }
+}
+
+/**
+ * Nearest entry of the color cube or the gray ramp. The system colors are left out as their
+ * actual values are up to the terminal.
+ */
+fn to_256(color: RGBA) -> u8 {
+    let level = |value: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&level| CUBE_LEVELS[level].abs_diff(value))
+            .unwrap() as u8
+    };
+    let (red, green, blue) = (level(color.red), level(color.green), level(color.blue));
+    let cube = (
+        CUBE_LEVELS[red as usize],
+        CUBE_LEVELS[green as usize],
+        CUBE_LEVELS[blue as usize],
+    );
+
+    // The ramp runs from 8 to 238 in steps of 10
+    let average = (color.red as u32 + color.green as u32 + color.blue as u32) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + 10 * step;
+
+    if distance(color, (gray, gray, gray)) < distance(color, cube) {
+        232 + step
+    } else {
+        16 + 36 * red + 6 * green + blue
+    }
+}
+
+fn to_16(color: RGBA) -> u8 {
+    (0..SYSTEM_COLORS.len())
+        .min_by_key(|&index| distance(color, SYSTEM_COLORS[index]))
+        .unwrap() as u8
+}
+
+/**
+ * Squared distance between two colors
+ */
+fn distance(color: RGBA, (red, green, blue): (u8, u8, u8)) -> u32 {
+    let square = |lhs: u8, rhs: u8| (lhs.abs_diff(rhs) as u32).pow(2);
+    square(color.red, red) + square(color.green, green) + square(color.blue, blue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rgb(red: u8, green: u8, blue: u8) -> RGBA {
+        RGBA::rgb(red, green, blue)
+    }
+
+    fn detect(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> ColorDepth {
+        ColorDepth::from_env(no_color.map(OsString::from), colorterm.map(OsString::from), term.map(OsString::from))
+    }
+
+    #[test]
+    fn detection() {
+        assert_eq!(detect(None, Some("truecolor"), Some("xterm-256color")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("24bit"), None), ColorDepth::TrueColor);
+        assert_eq!(detect(None, None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(detect(None, Some("yes"), Some("screen-256color")), ColorDepth::Ansi256);
+        assert_eq!(detect(None, None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None, None), ColorDepth::Ansi16);
+        assert_eq!(detect(None, None, Some("dumb")), ColorDepth::Monochrome);
+        assert_eq!(detect(Some("1"), Some("truecolor"), None), ColorDepth::Monochrome);
+        // An empty NO_COLOR counts as unset
+        assert_eq!(detect(Some(""), Some("truecolor"), None), ColorDepth::TrueColor);
+    }
+
+    #[test]
+    fn every_name_parses_back() {
+        for name in NAMES {
+            assert_eq!(ColorDepth::parse(name).unwrap().to_string(), name);
+        }
+        assert_eq!(ColorDepth::parse("8"), None);
+    }
+
+    #[test]
+    fn cube_colors_map_to_themselves() {
+        for (index, (red, green, blue)) in [(0, 0, 0), (255, 0, 0), (95, 135, 175), (255, 255, 255)]
+            .into_iter()
+            .enumerate()
+        {
+            let expected = [16, 196, 67, 231][index];
+            assert_eq!(to_256(rgb(red, green, blue)), expected);
+        }
+    }
+
+    #[test]
+    fn near_colors_round_to_the_cube() {
+        assert_eq!(to_256(rgb(250, 5, 3)), 196);
+        // 120 is closer to 135 than to 95
+        assert_eq!(to_256(rgb(0, 120, 0)), 16 + 6 * 2);
+        assert_eq!(to_256(rgb(97, 175, 239)), 16 + 36 + 6 * 3 + 5);
+    }
+
+    #[test]
+    fn grays_use_the_ramp() {
+        assert_eq!(to_256(rgb(8, 8, 8)), 232);
+        assert_eq!(to_256(rgb(128, 128, 128)), 232 + 12);
+        assert_eq!(to_256(rgb(238, 238, 238)), 255);
+        // Close to gray but not quite, still nearer to the ramp than to the cube
+        assert_eq!(to_256(rgb(39, 39, 44)), 232 + 3);
+    }
+
+    #[test]
+    fn sixteen_colors() {
+        assert_eq!(to_16(rgb(0, 0, 0)), 0);
+        assert_eq!(to_16(rgb(200, 10, 10)), 1);
+        assert_eq!(to_16(rgb(250, 20, 20)), 9);
+        assert_eq!(to_16(rgb(120, 120, 130)), 8);
+        assert_eq!(to_16(rgb(220, 220, 224)), 7);
+        assert_eq!(to_16(rgb(250, 250, 250)), 15);
+        assert_eq!(to_16(rgb(97, 175, 239)), 12);
+    }
+
+    #[test]
+    fn conversion_by_depth() {
+        let color = rgb(255, 0, 0);
+
+        assert_eq!(ColorDepth::TrueColor.convert(color), TermColor::Rgb(255, 0, 0));
+        assert_eq!(ColorDepth::Ansi256.convert(color), TermColor::Indexed(196));
+        assert_eq!(ColorDepth::Ansi16.convert(color), TermColor::Ansi(9));
+        assert_eq!(ColorDepth::Monochrome.convert(color), TermColor::Default);
+        assert_eq!(ColorDepth::TrueColor.convert(RGBA::transparent()), TermColor::Default);
+    }
+}
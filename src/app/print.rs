@@ -0,0 +1,131 @@
+use std::{
+    io::{self, stdout, Write},
+    time::Duration,
+};
+
+use super::{
+    buffer::CellBuffer,
+    event::Event,
+    export::Format,
+    renderer::{Area, Renderer},
+    screen::{Result, ScreenApi, ScreenOptions},
+};
+
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+/**
+ * Screen writing every frame to an output instead of a terminal, for logs and pipes. By default
+ * frames are plain text: no escape codes, no colors and the terminal is left alone. Trailing
+ * blanks are trimmed from every row.
+ *
+ * There is no input, polling never returns an event. Use `App::render_once` to draw a frame.
+ */
+pub struct PrintScreen {
+    output: Box<dyn Write>,
+    area: Area,
+    format: Format,
+}
+
+impl PrintScreen {
+    pub fn with_size(cols: u16, rows: u16) -> Self {
+        Self::with_output(Box::new(stdout()), cols, rows)
+    }
+
+    pub fn with_output(output: Box<dyn Write>, cols: u16, rows: u16) -> Self {
+        Self {
+            output,
+            area: Area {
+                x: 0,
+                y: 0,
+                cols,
+                rows,
+            },
+            format: Format::Text,
+        }
+    }
+
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn print(&mut self, buffer: &CellBuffer) -> io::Result<()> {
+        self.output.write_all(self.format.export(buffer).as_bytes())?;
+        self.output.flush()
+    }
+}
+
+impl ScreenApi for PrintScreen {
+    fn new(_: &ScreenOptions) -> Result<Self> {
+        Ok(Self::with_size(DEFAULT_COLS, DEFAULT_ROWS))
+    }
+
+    fn poll_event(&mut self, _: Duration) -> Result<Option<Event>> {
+        Ok(None)
+    }
+
+    fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> Result<()> {
+        let mut buffer = CellBuffer::empty(self.area);
+        draw(&mut buffer);
+        self.print(&buffer)?;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+    use crate::app::{view::HomeView, App};
+
+    #[derive(Clone, Default)]
+    struct SharedOutput(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn renders_one_plain_frame() {
+        let output = SharedOutput::default();
+        let screen = PrintScreen::with_output(Box::new(output.clone()), 30, 12);
+        App::with_root_view(screen, Box::new(HomeView::new()))
+            .render_once()
+            .unwrap();
+
+        let text = String::from_utf8(output.0.borrow().clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "This is some text");
+        assert!(lines.iter().all(|line| !line.ends_with(' ')));
+        assert!(!text.contains('\x1b'));
+    }
+
+    #[test]
+    fn renders_html() {
+        let output = SharedOutput::default();
+        let screen = PrintScreen::with_output(Box::new(output.clone()), 30, 12)
+            .with_format(Format::Html);
+        App::with_root_view(screen, Box::new(HomeView::new()))
+            .render_once()
+            .unwrap();
+
+        let html = String::from_utf8(output.0.borrow().clone()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("This is some text"));
+        // The accent swatch of the default dark theme
+        assert!(html.contains("background: #61afef;"));
+    }
+}
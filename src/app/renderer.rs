@@ -1,22 +1,211 @@
-#[cfg(feature = "ratatui")]
-pub use crate::app::tui::TuiRenderer as SpecializedRenderer;
-
-use super::ui::Style;
+use super::ui::{Color, Style, Theme, RGBA};
 
+/**
+ * Backend-neutral drawing surface. Positions are absolute cells of the frame, anything outside of
+ * the frame is skipped. Colors are blended by their alpha over whatever was drawn there before, so
+ * fully transparent ones keep it.
+ */
 pub trait Renderer {
     fn frame_size(&self) -> Area;
+
+    /**
+     * Theme that widgets resolve their color roles with, the dark one unless the app says
+     * otherwise
+     */
+    fn theme(&self) -> &Theme {
+        &DEFAULT_THEME
+    }
+
+    fn resolve(&self, color: Color) -> RGBA {
+        color.resolve(self.theme())
+    }
+
+    /**
+     * Draw `text` on row `y` starting at column `x`, one character per cell
+     */
+    fn put_str(&mut self, x: u16, y: u16, text: &str, foreground: RGBA, background: RGBA);
+
+    /**
+     * Paint the background of every cell in `area`, the characters are kept
+     */
+    fn fill_rect(&mut self, area: Area, background: RGBA);
+
+    /**
+     * Show the terminal cursor at (x, y) once the frame is drawn, it is hidden otherwise
+     */
+    // No widget takes text input yet
+    #[allow(dead_code)]
+    fn set_cursor(&mut self, x: u16, y: u16);
+
+    /**
+     * Draw the sides of `area` listed in `style.borders` with box-drawing characters in the
+     * foreground color of `style`
+     */
+    fn draw_border(&mut self, area: Area, style: &Style) {
+        if area.cols == 0 || area.rows == 0 {
+            return;
+        }
+        let borders = style.borders;
+        let foreground = self.resolve(style.foreground);
+        let right = area.x + area.cols - 1;
+        let bottom = area.y + area.rows - 1;
+        let mut put = |x: u16, y: u16, symbol: &str| {
+            self.put_str(x, y, symbol, foreground, RGBA::transparent());
+        };
+
+        for y in area.y..=bottom {
+            if borders.left {
+                put(area.x, y, "│");
+            }
+            if borders.right {
+                put(right, y, "│");
+            }
+        }
+        let horizontal = "─".repeat(area.cols as usize);
+        if borders.top {
+            put(area.x, area.y, &horizontal);
+        }
+        if borders.bottom {
+            put(area.x, bottom, &horizontal);
+        }
+
+        if borders.top && borders.left {
+            put(area.x, area.y, "┌");
+        }
+        if borders.top && borders.right {
+            put(right, area.y, "┐");
+        }
+        if borders.bottom && borders.left {
+            put(area.x, bottom, "└");
+        }
+        if borders.bottom && borders.right {
+            put(right, bottom, "┘");
+        }
+    }
+
     fn render(&mut self, widget: &mut dyn Widget, area: Area, style: &Style);
 }
 
-pub trait Widget 
-{
-    fn render(&mut self, renderer: &mut SpecializedRenderer, area: Area, style: &Style);
+static DEFAULT_THEME: Theme = Theme::dark();
+
+/**
+ * Hands `theme` to the widgets drawn on `renderer`
+ */
+pub struct Themed<'a> {
+    renderer: &'a mut dyn Renderer,
+    theme: &'a Theme,
+}
+
+impl<'a> Themed<'a> {
+    pub fn from(renderer: &'a mut dyn Renderer, theme: &'a Theme) -> Self {
+        Self { renderer, theme }
+    }
 }
 
-#[derive(Copy, Clone, PartialEq,  Eq, Debug)]
+impl Renderer for Themed<'_> {
+    fn frame_size(&self) -> Area {
+        self.renderer.frame_size()
+    }
+
+    fn theme(&self) -> &Theme {
+        self.theme
+    }
+
+    fn put_str(&mut self, x: u16, y: u16, text: &str, foreground: RGBA, background: RGBA) {
+        self.renderer.put_str(x, y, text, foreground, background);
+    }
+
+    fn fill_rect(&mut self, area: Area, background: RGBA) {
+        self.renderer.fill_rect(area, background);
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) {
+        self.renderer.set_cursor(x, y);
+    }
+
+    // Widgets have to draw on this renderer, not the wrapped one, to see the theme
+    fn render(&mut self, widget: &mut dyn Widget, area: Area, style: &Style) {
+        widget.render(self, area, style);
+    }
+}
+
+pub trait Widget {
+    fn render(&mut self, renderer: &mut dyn Renderer, area: Area, style: &Style);
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct Area {
     pub x: u16,
     pub y: u16,
     pub cols: u16,
     pub rows: u16,
 }
+
+impl Area {
+    /**
+     * Whether the cell at (col, row) lies inside this area
+     */
+    pub fn contains(&self, col: u16, row: u16) -> bool {
+        col >= self.x
+            && row >= self.y
+            && (col - self.x) < self.cols
+            && (row - self.y) < self.rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{buffer::CellBuffer, ui::Borders};
+
+    #[test]
+    fn contains() {
+        let area = Area {
+            x: 4,
+            y: 2,
+            cols: 3,
+            rows: 2,
+        };
+
+        assert!(area.contains(4, 2));
+        assert!(area.contains(6, 3));
+        assert!(area.contains(5, 2));
+
+        assert!(!area.contains(3, 2));
+        assert!(!area.contains(4, 1));
+        assert!(!area.contains(7, 2));
+        assert!(!area.contains(4, 4));
+        assert!(!area.contains(u16::MAX, u16::MAX));
+    }
+
+    #[test]
+    fn empty_area_contains_nothing() {
+        let area = Area {
+            x: 4,
+            y: 2,
+            cols: 0,
+            rows: 5,
+        };
+
+        assert!(!area.contains(4, 2));
+    }
+
+    #[test]
+    fn draw_border() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 4,
+            rows: 3,
+        };
+        let mut buffer = CellBuffer::empty(area);
+        buffer.draw_border(area, &Style::bordered());
+        assert_eq!(buffer.to_text(), "┌──┐\n│  │\n└──┘");
+
+        let mut buffer = CellBuffer::empty(area);
+        let style = Style::from(Borders::from(true, false, true, false), RGBA::transparent(), RGBA::rgb(255, 0, 0));
+        buffer.draw_border(area, &style);
+        assert_eq!(buffer.to_text(), "┌───\n│   \n│   ");
+        assert_eq!(buffer.get(3, 0).unwrap().foreground, RGBA::rgb(255, 0, 0));
+    }
+}
@@ -1,48 +1,66 @@
-use std::{io, result};
+use std::{error, fmt, io, result, time::Duration};
 
+#[cfg(not(any(feature = "ratatui", feature = "crossterm")))]
+compile_error!("enable the `ratatui` or the `crossterm` feature to pick a terminal backend");
+
+// The crossterm backend is only used without ratatui, e.g. `--no-default-features --features
+// crossterm`, so enabling both never builds ratatui for nothing
+#[cfg(all(feature = "crossterm", not(feature = "ratatui")))]
+pub use crate::app::term::Screen;
 #[cfg(feature = "ratatui")]
 pub use crate::app::tui::Screen;
 
-use super::view::View;
+use super::{event::Event, palette::ColorDepth, renderer::Renderer};
+
+/**
+ * How a terminal screen is set up, screens that do not draw to a terminal ignore it
+ */
+pub struct ScreenOptions {
+    /**
+     * Draw on the alternate screen, which is discarded on exit. Without it the frames are drawn
+     * over the shell and the last one stays in the scrollback.
+     */
+    pub alt_screen: bool,
+    /**
+     * Colors are brought down to what the terminal can show
+     */
+    pub color_depth: ColorDepth,
+}
 
 pub trait ScreenApi {
-    fn new() -> Result<Self>
+    fn new(options: &ScreenOptions) -> Result<Self>
     where
         Self: Sized;
     /**
-     * # Return a boolean indicating whether the program should keep running
+     * Wait up to `timeout` for the next event, `None` if nothing arrived in time
+     */
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>>;
+    /**
+     * Draw a frame, `draw` is handed a renderer covering the whole screen
      */
-    fn update(&mut self, view: &mut Box<dyn View>) -> Result<UpdateAction>;
-    fn render(&mut self, view: &mut Box<dyn View>);
+    fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> Result<()>;
     fn close(&mut self) -> Result<()>;
 }
 
-pub type ViewLoader = fn() -> Box<dyn View>;
-
-pub struct UpdateAction {
-    keep_running: bool,
-    view_loader: Option<ViewLoader>,
+#[derive(Debug)]
+pub enum Error {
+    IOError(io::Error),
 }
 
-impl UpdateAction {
-    pub fn from(keep_running: bool, view_loader: Option<ViewLoader>) -> Self {
-        Self {
-            keep_running,
-            view_loader,
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::IOError(_) => f.write_str("terminal I/O failed"),
         }
     }
-
-    pub fn get_keep_running(&self) -> bool {
-        self.keep_running
-    }
-    pub fn get_view_loader(&self) -> Option<ViewLoader> {
-        self.view_loader
-    }
 }
 
-#[derive(Debug)]
-pub enum Error {
-    IOError(io::Error),
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::IOError(err) => Some(err),
+        }
+    }
 }
 
 impl From<io::Error> for Error {
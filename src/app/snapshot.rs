@@ -0,0 +1,229 @@
+use std::{env, fs, path::PathBuf};
+
+use super::{
+    buffer::{Cell, CellBuffer},
+    headless::HeadlessScreen,
+    renderer::{Area, Renderer, Widget},
+    screen::ScreenApi,
+    ui::{Style, Theme, RGBA},
+    view::View,
+};
+
+/**
+ * Set to 1 to write the rendered output over the checked-in snapshots instead of comparing
+ */
+const BLESS_VAR: &str = "VISUALNV_BLESS";
+
+/**
+ * Render one frame of `view` into an off-screen buffer covering exactly `area`
+ */
+pub fn render_view(view: &mut dyn View, area: Area) -> CellBuffer {
+    let mut screen = HeadlessScreen::with_size(area.x + area.cols, area.y + area.rows);
+    let mut result = Ok(());
+    screen
+        .render(&mut |renderer: &mut dyn Renderer| {
+            result = view.render(&mut Viewport { renderer, area });
+        })
+        .unwrap();
+    if let Err(err) = result {
+        panic!("rendering the view failed: {err}");
+    }
+    screen.buffer().crop(area)
+}
+
+/**
+ * Compare the characters of `buffer` against `snapshots/<name>.snap`
+ */
+pub fn assert_snapshot(name: &str, buffer: &CellBuffer) {
+    compare(name, &format_text(buffer));
+}
+
+/**
+ * Compare the characters and colors of `buffer` against `snapshots/<name>.snap`
+ */
+pub fn assert_styled_snapshot(name: &str, buffer: &CellBuffer) {
+    compare(name, &format!("{}{}", format_text(buffer), format_styles(buffer)));
+}
+
+/**
+ * Renderer showing views a smaller frame than the screen
+ */
+struct Viewport<'a> {
+    renderer: &'a mut dyn Renderer,
+    area: Area,
+}
+
+impl Renderer for Viewport<'_> {
+    fn frame_size(&self) -> Area {
+        self.area
+    }
+
+    fn theme(&self) -> &Theme {
+        self.renderer.theme()
+    }
+
+    fn put_str(&mut self, x: u16, y: u16, text: &str, foreground: RGBA, background: RGBA) {
+        self.renderer.put_str(x, y, text, foreground, background);
+    }
+
+    fn fill_rect(&mut self, area: Area, background: RGBA) {
+        self.renderer.fill_rect(area, background);
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) {
+        self.renderer.set_cursor(x, y);
+    }
+
+    fn render(&mut self, widget: &mut dyn Widget, area: Area, style: &Style) {
+        widget.render(self, area, style);
+    }
+}
+
+fn compare(name: &str, actual: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/app/snapshots")
+        .join(format!("{name}.snap"));
+
+    if env::var(BLESS_VAR).is_ok_and(|value| value == "1") {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        Ok(expected) => expected,
+        Err(_) => panic!(
+            "snapshot {} does not exist, run with {BLESS_VAR}=1 to create it. Rendered:\n{actual}",
+            path.display()
+        ),
+    };
+    if expected != actual {
+        panic!(
+            "snapshot {name} does not match, run with {BLESS_VAR}=1 to accept the new output\n{}",
+            diff(&expected, actual)
+        );
+    }
+}
+
+/**
+ * Size header followed by one line per row. Rows are fenced with `|` so trailing blanks survive
+ * editors and diffs.
+ */
+fn format_text(buffer: &CellBuffer) -> String {
+    let area = buffer.area();
+    let mut text = format!("{}x{}\n", area.cols, area.rows);
+    for y in area.y..area.y + area.rows {
+        text.push('|');
+        text.push_str(&buffer.row_text(y));
+        text.push_str("|\n");
+    }
+    text
+}
+
+/**
+ * One line per run of cells sharing colors, cells without any color are left out
+ */
+fn format_styles(buffer: &CellBuffer) -> String {
+    let area = buffer.area();
+    let mut text = String::from("styles\n");
+    for y in area.y..area.y + area.rows {
+        let mut x = area.x;
+        while x < area.x + area.cols {
+            let cell = *buffer.get(x, y).unwrap();
+            let run_start = x;
+            while x < area.x + area.cols && same_style(buffer.get(x, y).unwrap(), &cell) {
+                x += 1;
+            }
+            if cell.foreground.is_transparent() && cell.background.is_transparent() {
+                continue;
+            }
+            text.push_str(&format!(
+                "{y:>3} {run_start:>3}..{:<3} fg {} bg {}\n",
+                x - 1,
+                format_color(&cell.foreground),
+                format_color(&cell.background)
+            ));
+        }
+    }
+    text
+}
+
+fn same_style(lhs: &Cell, rhs: &Cell) -> bool {
+    lhs.foreground == rhs.foreground && lhs.background == rhs.background
+}
+
+fn format_color(color: &RGBA) -> String {
+    match color.alpha {
+        0 => String::from("-------"),
+        u8::MAX => format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue),
+        alpha => format!("#{:02x}{:02x}{:02x}{alpha:02x}", color.red, color.green, color.blue),
+    }
+}
+
+/**
+ * Line by line comparison, both sides describe the same rows so lines are matched by position
+ */
+fn diff(expected: &str, actual: &str) -> String {
+    let expected_lines: Vec<&str> = expected.lines().collect();
+    let actual_lines: Vec<&str> = actual.lines().collect();
+    let mut text = String::new();
+    for line in 0..expected_lines.len().max(actual_lines.len()) {
+        let expected_line = expected_lines.get(line);
+        let actual_line = actual_lines.get(line);
+        if expected_line == actual_line {
+            continue;
+        }
+        if let Some(expected_line) = expected_line {
+            text.push_str(&format!("{:>4} - {expected_line}\n", line + 1));
+        }
+        if let Some(actual_line) = actual_line {
+            text.push_str(&format!("{:>4} + {actual_line}\n", line + 1));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_format() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 3,
+            rows: 2,
+        });
+        buffer.get_mut(0, 0).unwrap().symbol = 'a';
+        buffer.get_mut(2, 1).unwrap().symbol = 'b';
+
+        assert_eq!(format_text(&buffer), "3x2\n|a  |\n|  b|\n");
+    }
+
+    #[test]
+    fn style_runs() {
+        let mut buffer = CellBuffer::empty(Area {
+            x: 0,
+            y: 0,
+            cols: 4,
+            rows: 1,
+        });
+        buffer.get_mut(1, 0).unwrap().foreground = RGBA::rgb(255, 0, 0);
+        buffer.get_mut(2, 0).unwrap().foreground = RGBA::rgb(255, 0, 0);
+        buffer.get_mut(3, 0).unwrap().background = RGBA::rgb(0, 0, 255);
+
+        assert_eq!(
+            format_styles(&buffer),
+            "styles\n  0   1..2   fg #ff0000 bg -------\n  0   3..3   fg ------- bg #0000ff\n"
+        );
+    }
+
+    #[test]
+    fn diff_shows_changed_lines_only() {
+        assert_eq!(
+            diff("3x2\n|a  |\n|  b|\n", "3x2\n|a  |\n|  c|\n|   |\n"),
+            "   3 - |  b|\n   3 + |  c|\n   4 + |   |\n"
+        );
+    }
+}
@@ -0,0 +1,342 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseEvent, MouseEventKind};
+
+use crate::app::event::{self, Event, Key, Modifiers, MouseButton, MouseKind};
+
+/**
+ * Event to hand to the app, `None` for key releases. Terminals with enhanced keyboard reporting
+ * send those after every press, the app would act on each key twice.
+ */
+pub fn from_crossterm(value: crossterm::event::Event) -> Option<Event> {
+    match value {
+        crossterm::event::Event::Key(KeyEvent {
+            kind: KeyEventKind::Press | KeyEventKind::Repeat,
+            ..
+        }) => Some(value.into()),
+        crossterm::event::Event::Key(_) => None,
+        value => Some(value.into()),
+    }
+}
+
+impl From<crossterm::event::Event> for Event {
+    fn from(value: crossterm::event::Event) -> Self {
+        match value {
+            crossterm::event::Event::Key(key) => key.into(),
+            crossterm::event::Event::Mouse(mouse) => mouse.into(),
+            crossterm::event::Event::Resize(cols, rows) => Self::Resize { cols, rows },
+            crossterm::event::Event::FocusGained => Self::FocusGained,
+            crossterm::event::Event::FocusLost => Self::FocusLost,
+            crossterm::event::Event::Paste(text) => Self::Paste(text),
+        }
+    }
+}
+
+impl From<KeyEvent> for Event {
+    fn from(value: KeyEvent) -> Self {
+        Self::Key(value.into())
+    }
+}
+
+impl From<MouseEvent> for Event {
+    fn from(value: MouseEvent) -> Self {
+        Self::Mouse(value.into())
+    }
+}
+
+impl From<KeyEvent> for event::KeyEvent {
+    fn from(value: KeyEvent) -> Self {
+        let key = match value.code {
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::BackTab => Key::BackTab,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Insert => Key::Insert,
+            KeyCode::F(n) => Key::F(n),
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Esc => Key::Esc,
+            KeyCode::Null
+            | KeyCode::CapsLock
+            | KeyCode::ScrollLock
+            | KeyCode::NumLock
+            | KeyCode::PrintScreen
+            | KeyCode::Pause
+            | KeyCode::Menu
+            | KeyCode::KeypadBegin
+            | KeyCode::Media(_)
+            | KeyCode::Modifier(_) => Key::Unknown,
+        };
+
+        Self::from(key).with_modifiers(value.modifiers.into())
+    }
+}
+
+impl From<MouseEvent> for event::MouseEvent {
+    fn from(value: MouseEvent) -> Self {
+        let kind = match value.kind {
+            MouseEventKind::Down(button) => MouseKind::Press(button.into()),
+            MouseEventKind::Up(button) => MouseKind::Release(button.into()),
+            MouseEventKind::Drag(button) => MouseKind::Drag(button.into()),
+            MouseEventKind::Moved => MouseKind::Moved,
+            MouseEventKind::ScrollUp => MouseKind::ScrollUp,
+            MouseEventKind::ScrollDown => MouseKind::ScrollDown,
+            MouseEventKind::ScrollLeft => MouseKind::ScrollLeft,
+            MouseEventKind::ScrollRight => MouseKind::ScrollRight,
+        };
+
+        Self::from(kind, value.column, value.row)
+    }
+}
+
+impl From<crossterm::event::MouseButton> for MouseButton {
+    fn from(value: crossterm::event::MouseButton) -> Self {
+        match value {
+            crossterm::event::MouseButton::Left => MouseButton::Left,
+            crossterm::event::MouseButton::Right => MouseButton::Right,
+            crossterm::event::MouseButton::Middle => MouseButton::Middle,
+        }
+    }
+}
+
+impl From<KeyModifiers> for Modifiers {
+    fn from(value: KeyModifiers) -> Self {
+        let mut ret = Modifiers::NONE;
+        if value.contains(KeyModifiers::SHIFT) {
+            ret |= Modifiers::SHIFT;
+        }
+        if value.contains(KeyModifiers::CONTROL) {
+            ret |= Modifiers::CTRL;
+        }
+        if value.contains(KeyModifiers::ALT) {
+            ret |= Modifiers::ALT;
+        }
+        if value.contains(KeyModifiers::SUPER) {
+            ret |= Modifiers::SUPER;
+        }
+        ret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crossterm::event::MouseButton as CrosstermButton;
+    use crossterm::event::{MediaKeyCode, ModifierKeyCode};
+
+    use super::*;
+
+    fn key_of(code: KeyCode) -> Key {
+        let event: event::KeyEvent = KeyEvent::new(code, KeyModifiers::NONE).into();
+        *event.get_key()
+    }
+
+    fn modifiers_of(modifiers: KeyModifiers) -> Modifiers {
+        let event: event::KeyEvent = KeyEvent::new(KeyCode::Char('a'), modifiers).into();
+        event.get_modifiers()
+    }
+
+    fn mouse_of(kind: MouseEventKind) -> event::MouseEvent {
+        MouseEvent {
+            kind,
+            column: 12,
+            row: 7,
+            modifiers: KeyModifiers::NONE,
+        }
+        .into()
+    }
+
+    #[test]
+    fn named_keys() {
+        assert_eq!(key_of(KeyCode::Enter), Key::Enter);
+        assert_eq!(key_of(KeyCode::Esc), Key::Esc);
+        assert_eq!(key_of(KeyCode::Backspace), Key::Backspace);
+        assert_eq!(key_of(KeyCode::Delete), Key::Delete);
+        assert_eq!(key_of(KeyCode::Insert), Key::Insert);
+        assert_eq!(key_of(KeyCode::Tab), Key::Tab);
+        assert_eq!(key_of(KeyCode::BackTab), Key::BackTab);
+    }
+
+    #[test]
+    fn navigation_keys() {
+        assert_eq!(key_of(KeyCode::Left), Key::Left);
+        assert_eq!(key_of(KeyCode::Right), Key::Right);
+        assert_eq!(key_of(KeyCode::Up), Key::Up);
+        assert_eq!(key_of(KeyCode::Down), Key::Down);
+        assert_eq!(key_of(KeyCode::Home), Key::Home);
+        assert_eq!(key_of(KeyCode::End), Key::End);
+        assert_eq!(key_of(KeyCode::PageUp), Key::PageUp);
+        assert_eq!(key_of(KeyCode::PageDown), Key::PageDown);
+    }
+
+    #[test]
+    fn characters_and_function_keys() {
+        assert_eq!(key_of(KeyCode::Char('q')), Key::Char('q'));
+        assert_eq!(key_of(KeyCode::Char('Q')), Key::Char('Q'));
+        assert_eq!(key_of(KeyCode::Char(' ')), Key::Char(' '));
+        assert_eq!(key_of(KeyCode::Char('é')), Key::Char('é'));
+        assert_eq!(key_of(KeyCode::F(1)), Key::F(1));
+        assert_eq!(key_of(KeyCode::F(12)), Key::F(12));
+    }
+
+    #[test]
+    fn unsupported_keys() {
+        assert_eq!(key_of(KeyCode::Null), Key::Unknown);
+        assert_eq!(key_of(KeyCode::CapsLock), Key::Unknown);
+        assert_eq!(key_of(KeyCode::ScrollLock), Key::Unknown);
+        assert_eq!(key_of(KeyCode::NumLock), Key::Unknown);
+        assert_eq!(key_of(KeyCode::PrintScreen), Key::Unknown);
+        assert_eq!(key_of(KeyCode::Pause), Key::Unknown);
+        assert_eq!(key_of(KeyCode::Menu), Key::Unknown);
+        assert_eq!(key_of(KeyCode::KeypadBegin), Key::Unknown);
+        assert_eq!(key_of(KeyCode::Media(MediaKeyCode::Play)), Key::Unknown);
+        assert_eq!(
+            key_of(KeyCode::Modifier(ModifierKeyCode::LeftShift)),
+            Key::Unknown
+        );
+    }
+
+    #[test]
+    fn modifiers() {
+        assert_eq!(modifiers_of(KeyModifiers::NONE), Modifiers::NONE);
+        assert_eq!(modifiers_of(KeyModifiers::SHIFT), Modifiers::SHIFT);
+        assert_eq!(modifiers_of(KeyModifiers::CONTROL), Modifiers::CTRL);
+        assert_eq!(modifiers_of(KeyModifiers::ALT), Modifiers::ALT);
+        assert_eq!(modifiers_of(KeyModifiers::SUPER), Modifiers::SUPER);
+
+        let combined = modifiers_of(KeyModifiers::CONTROL | KeyModifiers::SHIFT);
+        assert!(combined.contains(Modifiers::CTRL));
+        assert!(combined.contains(Modifiers::SHIFT));
+        assert!(combined.contains(Modifiers::CTRL | Modifiers::SHIFT));
+        assert!(!combined.contains(Modifiers::ALT));
+    }
+
+    #[test]
+    fn mouse_buttons() {
+        assert_eq!(
+            *mouse_of(MouseEventKind::Down(CrosstermButton::Left)).get_kind(),
+            MouseKind::Press(MouseButton::Left)
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::Up(CrosstermButton::Right)).get_kind(),
+            MouseKind::Release(MouseButton::Right)
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::Drag(CrosstermButton::Middle)).get_kind(),
+            MouseKind::Drag(MouseButton::Middle)
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::Moved).get_kind(),
+            MouseKind::Moved
+        );
+    }
+
+    #[test]
+    fn mouse_scroll() {
+        assert_eq!(
+            *mouse_of(MouseEventKind::ScrollUp).get_kind(),
+            MouseKind::ScrollUp
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::ScrollDown).get_kind(),
+            MouseKind::ScrollDown
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::ScrollLeft).get_kind(),
+            MouseKind::ScrollLeft
+        );
+        assert_eq!(
+            *mouse_of(MouseEventKind::ScrollRight).get_kind(),
+            MouseKind::ScrollRight
+        );
+    }
+
+    #[test]
+    fn mouse_position() {
+        let event: event::MouseEvent = MouseEvent {
+            kind: MouseEventKind::Down(CrosstermButton::Left),
+            column: 3,
+            row: 42,
+            modifiers: KeyModifiers::ALT,
+        }
+        .into();
+
+        assert_eq!(event.get_col(), 3);
+        assert_eq!(event.get_row(), 42);
+    }
+
+    #[test]
+    fn terminal_events() {
+        match crossterm::event::Event::Resize(120, 40).into() {
+            Event::Resize { cols, rows } => {
+                assert_eq!(cols, 120);
+                assert_eq!(rows, 40);
+            }
+            _ => panic!("expected a resize event"),
+        }
+
+        assert!(matches!(
+            crossterm::event::Event::FocusGained.into(),
+            Event::FocusGained
+        ));
+        assert!(matches!(
+            crossterm::event::Event::FocusLost.into(),
+            Event::FocusLost
+        ));
+
+        match crossterm::event::Event::Paste(String::from("some\npasted text")).into() {
+            Event::Paste(text) => assert_eq!(text, "some\npasted text"),
+            _ => panic!("expected a paste event"),
+        }
+    }
+
+    #[test]
+    fn input_events() {
+        let key = crossterm::event::Event::Key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE));
+        match key.into() {
+            Event::Key(key) => assert_eq!(*key.get_key(), Key::Tab),
+            _ => panic!("expected a key event"),
+        }
+
+        let mouse = crossterm::event::Event::Mouse(MouseEvent {
+            kind: MouseEventKind::ScrollDown,
+            column: 0,
+            row: 0,
+            modifiers: KeyModifiers::NONE,
+        });
+        match mouse.into() {
+            Event::Mouse(mouse) => assert_eq!(*mouse.get_kind(), MouseKind::ScrollDown),
+            _ => panic!("expected a mouse event"),
+        }
+    }
+
+    #[test]
+    fn key_releases_are_dropped() {
+        let with_kind = |kind| {
+            crossterm::event::Event::Key(KeyEvent::new_with_kind(
+                KeyCode::Enter,
+                KeyModifiers::NONE,
+                kind,
+            ))
+        };
+
+        assert!(matches!(
+            from_crossterm(with_kind(KeyEventKind::Press)),
+            Some(Event::Key(_))
+        ));
+        assert!(matches!(
+            from_crossterm(with_kind(KeyEventKind::Repeat)),
+            Some(Event::Key(_))
+        ));
+        assert!(from_crossterm(with_kind(KeyEventKind::Release)).is_none());
+        assert!(matches!(
+            from_crossterm(crossterm::event::Event::FocusGained),
+            Some(Event::FocusGained)
+        ));
+    }
+}
@@ -0,0 +1,7 @@
+pub mod event;
+pub mod terminal;
+#[cfg(all(feature = "crossterm", not(feature = "ratatui")))]
+pub mod screen;
+
+#[cfg(all(feature = "crossterm", not(feature = "ratatui")))]
+pub use screen::Screen;
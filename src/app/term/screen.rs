@@ -0,0 +1,221 @@
+use std::{
+    io::{self, stdout, BufWriter, Stdout, Write},
+    time::Duration,
+};
+
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event,
+    style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor},
+    terminal::{self as crossterm_terminal, Clear, ClearType},
+    QueueableCommand,
+};
+
+use crate::app::{
+    buffer::{Cell, CellBuffer},
+    event::Event,
+    palette::{ColorDepth, TermColor},
+    renderer::{Area, Renderer},
+    screen::{Result, ScreenApi, ScreenOptions},
+    term::{self, terminal},
+};
+
+/**
+ * Screen driving crossterm directly. Frames are drawn into a cell buffer and only the cells that
+ * changed since the previous frame are written to the terminal.
+ */
+pub struct Screen {
+    output: BufWriter<Stdout>,
+    current: CellBuffer,
+    previous: CellBuffer,
+    color_depth: ColorDepth,
+    closed: bool,
+}
+
+impl ScreenApi for Screen {
+    fn new(options: &ScreenOptions) -> Result<Self> {
+        terminal::install_panic_hook();
+        match Self::setup_terminal(options) {
+            Ok(screen) => Ok(screen),
+            Err(err) => {
+                let _ = terminal::restore();
+                Err(err.into())
+            }
+        }
+    }
+
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
+        }
+        let Some(evt) = term::event::from_crossterm(event::read()?) else {
+            return Ok(None);
+        };
+        if let Event::Resize { cols, rows } = evt {
+            self.resize(cols, rows)?;
+        }
+        Ok(Some(evt))
+    }
+
+    fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> Result<()> {
+        // The same buffers are drawn into frame after frame, they only grow on resize
+        self.current.reset(self.previous.area());
+        draw(&mut self.current);
+        self.flush()?;
+        std::mem::swap(&mut self.current, &mut self.previous);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.closed = true;
+        terminal::restore()?;
+        Ok(())
+    }
+}
+
+impl Screen {
+    fn setup_terminal(options: &ScreenOptions) -> io::Result<Self> {
+        terminal::setup(options)?;
+        let (cols, rows) = crossterm_terminal::size()?;
+        let mut screen = Self {
+            output: BufWriter::new(stdout()),
+            current: CellBuffer::empty(full_area(cols, rows)),
+            previous: CellBuffer::empty(full_area(cols, rows)),
+            color_depth: options.color_depth,
+            closed: false,
+        };
+        screen.resize(cols, rows)?;
+        Ok(screen)
+    }
+
+    /**
+     * Clear the terminal and forget the previous frame so the next one is drawn in full
+     */
+    fn resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.previous = CellBuffer::empty(full_area(cols, rows));
+        self.output.queue(ResetColor)?.queue(Clear(ClearType::All))?;
+        self.output.flush()
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let mut next_position = None;
+        let mut foreground = None;
+        let mut background = None;
+        for (x, y, cell) in changes(&self.previous, &self.current) {
+            if next_position != Some((x, y)) {
+                self.output.queue(MoveTo(x, y))?;
+            }
+            if foreground != Some(cell.foreground) {
+                self.output.queue(SetForegroundColor(to_color(self.color_depth.convert(cell.foreground))))?;
+                foreground = Some(cell.foreground);
+            }
+            if background != Some(cell.background) {
+                self.output.queue(SetBackgroundColor(to_color(self.color_depth.convert(cell.background))))?;
+                background = Some(cell.background);
+            }
+            self.output.queue(Print(cell.symbol))?;
+            next_position = Some((x + 1, y));
+        }
+        self.output.queue(ResetColor)?;
+
+        match self.current.cursor() {
+            Some((x, y)) => self.output.queue(MoveTo(x, y))?.queue(Show)?,
+            None => self.output.queue(Hide)?,
+        };
+        self.output.flush()
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if !self.closed {
+            let _ = terminal::restore();
+        }
+    }
+}
+
+fn full_area(cols: u16, rows: u16) -> Area {
+    Area {
+        x: 0,
+        y: 0,
+        cols,
+        rows,
+    }
+}
+
+/**
+ * Cells of `current` that differ from `previous`, row by row. Both buffers cover the same area.
+ */
+fn changes<'a>(
+    previous: &'a CellBuffer,
+    current: &'a CellBuffer,
+) -> impl Iterator<Item = (u16, u16, Cell)> + 'a {
+    let area = current.area();
+    (area.y..area.y + area.rows)
+        .flat_map(move |y| (area.x..area.x + area.cols).map(move |x| (x, y)))
+        .filter_map(move |(x, y)| {
+            let cell = *current.get(x, y)?;
+            (previous.get(x, y) != Some(&cell)).then_some((x, y, cell))
+        })
+}
+
+fn to_color(color: TermColor) -> Color {
+    match color {
+        TermColor::Default => Color::Reset,
+        TermColor::Rgb(r, g, b) => Color::Rgb { r, g, b },
+        TermColor::Indexed(index) => Color::AnsiValue(index),
+        // Named colors, which terminals without a 256 color palette understand too
+        TermColor::Ansi(index) => [
+            Color::Black,
+            Color::DarkRed,
+            Color::DarkGreen,
+            Color::DarkYellow,
+            Color::DarkBlue,
+            Color::DarkMagenta,
+            Color::DarkCyan,
+            Color::Grey,
+            Color::DarkGrey,
+            Color::Red,
+            Color::Green,
+            Color::Yellow,
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::White,
+        ][index as usize % 16],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ui::RGBA;
+
+    #[test]
+    fn only_changed_cells_are_flushed() {
+        let previous = CellBuffer::empty(full_area(4, 2));
+        let mut current = previous.clone();
+        current.put_str(1, 0, "ab", RGBA::transparent(), RGBA::transparent());
+        current.fill_rect(full_area(1, 2), RGBA::rgb(255, 0, 0));
+
+        let changed: Vec<(u16, u16, char)> = changes(&previous, &current)
+            .map(|(x, y, cell)| (x, y, cell.symbol))
+            .collect();
+        assert_eq!(changed, vec![(0, 0, ' '), (1, 0, 'a'), (2, 0, 'b'), (0, 1, ' ')]);
+    }
+
+    #[test]
+    fn identical_frames_flush_nothing() {
+        let mut frame = CellBuffer::empty(full_area(4, 2));
+        frame.put_str(0, 0, "same", RGBA::rgb(255, 0, 0), RGBA::rgb(0, 0, 255));
+
+        assert_eq!(changes(&frame, &frame.clone()).count(), 0);
+    }
+
+    #[test]
+    fn sixteen_colors_are_named() {
+        assert_eq!(to_color(ColorDepth::Ansi16.convert(RGBA::rgb(255, 0, 0))), Color::Red);
+        assert_eq!(to_color(ColorDepth::Ansi256.convert(RGBA::rgb(255, 0, 0))), Color::AnsiValue(196));
+        assert_eq!(to_color(ColorDepth::Monochrome.convert(RGBA::rgb(255, 0, 0))), Color::Reset);
+    }
+}
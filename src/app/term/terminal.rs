@@ -0,0 +1,76 @@
+use std::{
+    io::{self, stdout},
+    panic,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+};
+
+use crossterm::{
+    cursor::Show,
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    ExecutableCommand,
+};
+
+use crate::app::screen::ScreenOptions;
+
+static PANIC_HOOK: Once = Once::new();
+/**
+ * Whether `setup` switched to the alternate screen, the panic hook has to know what to undo
+ */
+static ALT_SCREEN: AtomicBool = AtomicBool::new(false);
+
+/**
+ * Switch to raw mode, on the alternate screen if asked to, and ask for mouse, paste and focus
+ * events
+ */
+pub fn setup(options: &ScreenOptions) -> io::Result<()> {
+    enable_raw_mode()?;
+    if options.alt_screen {
+        stdout().execute(EnterAlternateScreen)?;
+        ALT_SCREEN.store(true, Ordering::SeqCst);
+    }
+    stdout().execute(EnableMouseCapture)?;
+    stdout().execute(EnableBracketedPaste)?;
+    stdout().execute(EnableFocusChange)?;
+    Ok(())
+}
+
+/**
+ * Put the terminal back the way the shell expects it. Every step is attempted even if an earlier
+ * one fails, the first error is reported.
+ */
+pub fn restore() -> io::Result<()> {
+    let results = [
+        stdout().execute(DisableFocusChange).map(|_| ()),
+        stdout().execute(DisableBracketedPaste).map(|_| ()),
+        stdout().execute(DisableMouseCapture).map(|_| ()),
+        disable_raw_mode(),
+        if ALT_SCREEN.swap(false, Ordering::SeqCst) {
+            stdout().execute(LeaveAlternateScreen).map(|_| ())
+        } else {
+            Ok(())
+        },
+        stdout().execute(Show).map(|_| ()),
+    ];
+    results.into_iter().collect()
+}
+
+/**
+ * Restore the terminal before the default hook prints the panic, otherwise the message is lost on
+ * the alternate screen and the shell is left in raw mode
+ */
+pub fn install_panic_hook() {
+    PANIC_HOOK.call_once(|| {
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let _ = restore();
+            previous_hook(info);
+        }));
+    });
+}
@@ -0,0 +1,71 @@
+use ratatui::{layout::Rect, style::Color, Frame};
+use crate::app::{
+    buffer::CellBuffer,
+    palette::{ColorDepth, TermColor},
+    renderer::Area,
+};
+
+/**
+ * Copy a frame drawn into `buffer` to ratatui, which works out what changed on the terminal
+ */
+pub fn draw(frame: &mut Frame, buffer: &CellBuffer, color_depth: ColorDepth) {
+    // ratatui resizes the frame on its own, cells it does not have are dropped
+    let area = Area::from(Rect::from(buffer.area()).intersection(frame.size()));
+    let target = frame.buffer_mut();
+    for y in area.y..area.y + area.rows {
+        for x in area.x..area.x + area.cols {
+            let cell = buffer.get(x, y).unwrap();
+            let target_cell = target.get_mut(x, y);
+            target_cell.set_char(cell.symbol);
+            target_cell.set_fg(to_color(color_depth.convert(cell.foreground)));
+            target_cell.set_bg(to_color(color_depth.convert(cell.background)));
+        }
+    }
+    if let Some((x, y)) = buffer.cursor() {
+        frame.set_cursor(x, y);
+    }
+}
+
+fn to_color(color: TermColor) -> Color {
+    match color {
+        TermColor::Default => Color::Reset,
+        TermColor::Rgb(red, green, blue) => Color::Rgb(red, green, blue),
+        TermColor::Indexed(index) => Color::Indexed(index),
+        // Named colors, which terminals without a 256 color palette understand too
+        TermColor::Ansi(index) => [
+            Color::Black,
+            Color::Red,
+            Color::Green,
+            Color::Yellow,
+            Color::Blue,
+            Color::Magenta,
+            Color::Cyan,
+            Color::Gray,
+            Color::DarkGray,
+            Color::LightRed,
+            Color::LightGreen,
+            Color::LightYellow,
+            Color::LightBlue,
+            Color::LightMagenta,
+            Color::LightCyan,
+            Color::White,
+        ][index as usize % 16],
+    }
+}
+
+impl From<Area> for Rect {
+    fn from(value: Area) -> Self {
+        Self::new(value.x, value.y, value.cols, value.rows)
+    }
+}
+
+impl From<Rect> for Area {
+    fn from(value: Rect) -> Self {
+        Self {
+            x: value.x,
+            y: value.y,
+            cols: value.width,
+            rows: value.height,
+        }
+    }
+}
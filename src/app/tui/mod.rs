@@ -1,7 +1,4 @@
 pub mod screen;
-pub mod event;
-pub mod renderer;
-pub mod ui;
+pub mod frame;
 
 pub use screen::Screen;
-pub use renderer::TuiRenderer;
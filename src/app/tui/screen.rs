@@ -1,66 +1,96 @@
-use std::io::{stdout, Stdout};
-
-use crossterm::{
-    event,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen}, 
-    ExecutableCommand,
+use std::{
+    io::{self, stdout, Stdout},
+    time::Duration,
 };
-use ratatui::{backend::CrosstermBackend, Terminal};
+
+use crossterm::event;
+use ratatui::{backend::CrosstermBackend, layout::Rect, Terminal};
 
 use crate::app::{
-    event::{Event,Key}, 
-    screen::{Result, ScreenApi, UpdateAction}, 
-    view::View,
-    tui::TuiRenderer,
+    buffer::CellBuffer,
+    event::Event,
+    palette::ColorDepth,
+    renderer::{Area, Renderer},
+    screen::{Result, ScreenApi, ScreenOptions},
+    term::{self, terminal},
+    tui::frame,
 };
 
 pub struct Screen {
     terminal: Terminal<CrosstermBackend<Stdout>>,
+    color_depth: ColorDepth,
+    /**
+     * Drawn into again for every frame
+     */
+    buffer: CellBuffer,
+    closed: bool,
 }
 
 impl ScreenApi for Screen {
-    fn new() -> Result<Self> {
-        enable_raw_mode()?;
-        stdout().execute(EnterAlternateScreen)?;
-        let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
-
-        Ok(Self {
-            terminal,
-        })
-    }
-
-    fn update(&mut self, view: &mut Box<dyn View>) -> Result<UpdateAction> {
-        if event::poll(std::time::Duration::from_millis(0))? {
-            if let event::Event::Key(key) = event::read()? {
-                let evt: Event = key.into();
-                let _ = view.update(&evt);
-                match evt.get_key() {
-                    Key::ESC => return Ok(UpdateAction::from(false, None)),
-                    _ => {},
-                }
+    fn new(options: &ScreenOptions) -> Result<Self> {
+        terminal::install_panic_hook();
+        match Self::setup_terminal(options) {
+            Ok(terminal) => Ok(Self {
+                terminal,
+                color_depth: options.color_depth,
+                // Sized to the terminal on the first frame
+                buffer: CellBuffer::empty(Area {
+                    x: 0,
+                    y: 0,
+                    cols: 0,
+                    rows: 0,
+                }),
+                closed: false,
+            }),
+            Err(err) => {
+                let _ = terminal::restore();
+                Err(err.into())
             }
         }
-        Ok(UpdateAction::from(true, None))
     }
 
-    fn render(&mut self, view: &mut Box<dyn View>) {
-        match self.terminal.draw(move |frame| {
-            {
-                let mut renderer = TuiRenderer::from(frame);
-                let _ = view.render(&mut renderer);
-            }
-        }) {
-            Ok(_) => {},
-            Err(_) => {},
+    fn poll_event(&mut self, timeout: Duration) -> Result<Option<Event>> {
+        if !event::poll(timeout)? {
+            return Ok(None);
         }
+        let Some(evt) = term::event::from_crossterm(event::read()?) else {
+            return Ok(None);
+        };
+        if let Event::Resize { cols, rows } = evt {
+            self.terminal.resize(Rect::new(0, 0, cols, rows))?;
+        }
+        Ok(Some(evt))
+    }
+
+    fn render(&mut self, draw: &mut dyn FnMut(&mut dyn Renderer)) -> Result<()> {
+        // Frames are drawn into a cell buffer first, which is where colors are blended
+        self.buffer.reset(self.terminal.size()?.into());
+        draw(&mut self.buffer);
+        self.terminal.draw(|target| frame::draw(target, &self.buffer, self.color_depth))?;
+        Ok(())
     }
 
     fn close(&mut self) -> Result<()> {
-        disable_raw_mode()?;
-        stdout().execute(LeaveAlternateScreen)?;
+        self.closed = true;
+        terminal::restore()?;
         Ok(())
     }
 }
 
 impl Screen {
+    fn setup_terminal(options: &ScreenOptions) -> io::Result<Terminal<CrosstermBackend<Stdout>>> {
+        terminal::setup(options)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+        // Without the alternate screen the shell's output would show through blank cells
+        terminal.clear()?;
+        Ok(terminal)
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if !self.closed {
+            let _ = terminal::restore();
+        }
+    }
 }
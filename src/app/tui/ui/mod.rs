@@ -1,3 +0,0 @@
-mod label;
-mod surface;
-mod style;
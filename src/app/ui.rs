@@ -3,9 +3,11 @@ mod layout;
 mod style;
 mod surface;
 mod table;
+mod theme;
 
 pub use label::Label;
-pub use layout::{Direction, Layout, LayoutBuffer};
-pub use style::{Borders, Padding, Style, RGBA};
-pub use surface::{Surface, SurfaceOnRender};
+pub use layout::{Align, Constraint, Direction, Justify, Layout, LayoutBuffer, LayoutNode, LayoutTree};
+pub use style::{Borders, Color, Padding, Style, RGBA};
+pub use surface::Surface;
 pub use table::Table;
+pub use theme::{Role, Theme, NAMES as THEME_NAMES};
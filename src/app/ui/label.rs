@@ -1,3 +1,8 @@
+use crate::app::{
+    renderer::{Area, Renderer, Widget},
+    ui::{Style, RGBA},
+};
+
 pub struct Label {
     text: String,
 }
@@ -13,3 +18,16 @@ impl Label {
         &self.text
     }
 }
+
+impl Widget for Label {
+    /**
+     * One line of text per row, lines longer than the area are cut off
+     */
+    fn render(&mut self, renderer: &mut dyn Renderer, area: Area, style: &Style) {
+        let foreground = renderer.resolve(style.foreground);
+        for (y, line) in (area.y..area.y + area.rows).zip(self.get_text().lines()) {
+            let visible: String = line.chars().take(area.cols as usize).collect();
+            renderer.put_str(area.x, y, &visible, foreground, RGBA::transparent());
+        }
+    }
+}
@@ -1,99 +1,421 @@
 use crate::app::renderer::Area;
 
+use super::Padding;
+
+mod tree;
+
+pub use tree::{LayoutNode, LayoutTree};
+
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Direction {
     Vertical,
     Horizontal,
 }
 
+/**
+ * Where the parts go along the layout direction when they do not fill all of it
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Justify {
+    Start,
+    // No view centers or right-aligns its parts yet
+    #[allow(dead_code)]
+    Center,
+    #[allow(dead_code)]
+    End,
+    /**
+     * First part at the start, last at the end, the free space evenly between them
+     */
+    SpaceBetween,
+    /**
+     * Every part gets the same free space on both sides, so the ends get half of what is between
+     */
+    #[allow(dead_code)]
+    SpaceAround,
+}
+
+/**
+ * Where parts with a cross size go across the layout direction
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum Align {
+    Start,
+    Center,
+    // No view puts its parts at the far side yet
+    #[allow(dead_code)]
+    End,
+}
+
+/**
+ * How much of the parent area along the layout direction one part takes
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
 pub enum Layout {
     Static(u16),
+    /**
+     * Share of the space the fixed parts leave, by weight
+     */
     Weighted(u16),
+    /**
+     * Fraction of the parent between 0 and 1
+     */
     Percentage(f32),
+    /**
+     * Numerator and denominator of a fraction of the parent, without float rounding
+     */
+    Ratio(u16, u16),
+    /**
+     * Like `Fill`, but at least this size
+     */
+    Min(u16),
+    /**
+     * Like `Fill`, but at most this size
+     */
+    // No view caps a flexible part yet
+    #[allow(dead_code)]
+    Max(u16),
+    /**
+     * Same as `Weighted(1)`
+     */
+    Fill,
+}
+
+/**
+ * A layout kept within a minimum and a maximum size, e.g.
+ * `Layout::Weighted(2).with_min(10).with_max(40)`. Plain layouts convert into unbounded
+ * constraints.
+ */
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Constraint {
+    layout: Layout,
+    min: u16,
+    max: u16,
+    /**
+     * Size across the layout direction, `None` takes all of it
+     */
+    cross_size: Option<u16>,
 }
 
+impl Layout {
+    /**
+     * Start a constraint, `Constraint::with_max` adds an upper bound
+     */
+    pub fn with_min(self, min: u16) -> Constraint {
+        Constraint::from(self).with_min(min)
+    }
+
+    pub fn with_cross_size(self, cross_size: u16) -> Constraint {
+        Constraint::from(self).with_cross_size(cross_size)
+    }
+}
+
+impl Constraint {
+    pub fn with_min(mut self, min: u16) -> Self {
+        self.min = self.min.max(min);
+        self
+    }
+
+    pub fn with_max(mut self, max: u16) -> Self {
+        self.max = self.max.min(max);
+        self
+    }
+
+    /**
+     * Keep the part this wide, or high, across the layout direction and place it there following
+     * `LayoutBuffer::with_align`
+     */
+    pub fn with_cross_size(mut self, cross_size: u16) -> Self {
+        self.cross_size = Some(cross_size);
+        self
+    }
+
+    /**
+     * Weight of the parts that share the space left over, `None` for parts of a fixed size
+     */
+    fn weight(&self) -> Option<u16> {
+        match self.layout {
+            Layout::Weighted(weight) => Some(weight),
+            Layout::Min(_) | Layout::Max(_) | Layout::Fill => Some(1),
+            Layout::Static(_) | Layout::Percentage(_) | Layout::Ratio(..) => None,
+        }
+    }
+
+    /**
+     * The maximum wins over the minimum when they contradict each other
+     */
+    fn clamp(&self, size: u16) -> u16 {
+        size.max(self.min).min(self.max)
+    }
+}
+
+impl From<Layout> for Constraint {
+    fn from(layout: Layout) -> Self {
+        let unbounded = Self {
+            layout,
+            min: 0,
+            max: u16::MAX,
+            cross_size: None,
+        };
+        match layout {
+            Layout::Min(min) => unbounded.with_min(min),
+            Layout::Max(max) => unbounded.with_max(max),
+            _ => unbounded,
+        }
+    }
+}
+
+/**
+ * Splits an area into parts along one direction. The spacing and alignment are kept when the
+ * layouts are cleared, so they are usually set once with the `with_*` methods.
+ */
 pub struct LayoutBuffer {
-    layouts: Vec<Layout>,
+    layouts: Vec<Constraint>,
+    /**
+     * Size of each layout along the direction, reused between calculations
+     */
+    sizes: Vec<Option<u16>>,
     areas: Vec<Area>,
+    gap: u16,
+    margin: Padding,
+    justify: Justify,
+    align: Align,
 }
 
 impl LayoutBuffer {
     pub fn with_capacity(initial_capacity: usize) -> Self {
         Self {
             layouts: Vec::with_capacity(initial_capacity),
+            sizes: Vec::with_capacity(initial_capacity),
             areas: Vec::with_capacity(initial_capacity),
+            gap: 0,
+            margin: Padding::none(),
+            justify: Justify::Start,
+            align: Align::Start,
         }
     }
 
+    /**
+     * Space between two parts, taken before the layouts share the rest
+     */
+    pub fn with_gap(mut self, gap: u16) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /**
+     * Space kept free around all of the parts
+     */
+    pub fn with_margin(mut self, margin: Padding) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
     pub fn clear(&mut self) {
         self.layouts.clear();
     }
 
-    pub fn add_layout(&mut self, layout: Layout) -> &mut Self {
-        self.layouts.push(layout);
+    pub fn add_layout(&mut self, layout: impl Into<Constraint>) -> &mut Self {
+        self.layouts.push(layout.into());
         self
     }
 
+    /**
+     * Split `frame_area` along `direction`, one area per layout in the order they were added.
+     * Areas never reach past `frame_area` less the margin: when the layouts and gaps ask for more
+     * than there is, the last ones are cut short, even below their minimum.
+     */
     pub fn calculate_areas(&mut self, direction: Direction, frame_area: &Area) {
         self.areas.clear();
 
-        let mut statically_taken_size: u16 = 0;
-        let mut weight_sum: f32 = 0.0;
-        let total_size = match direction {
-            Direction::Vertical => frame_area.rows,
-            Direction::Horizontal => frame_area.cols,
+        let inner_area = self.margin.inside(*frame_area);
+        let (total_size, cross_total) = match direction {
+            Direction::Vertical => (inner_area.rows, inner_area.cols),
+            Direction::Horizontal => (inner_area.cols, inner_area.rows),
         };
+        let gap_count = self.layouts.len().saturating_sub(1) as u64;
+        let gaps = (self.gap as u64 * gap_count).min(total_size as u64) as u32;
+        self.calculate_sizes(total_size - gaps as u16);
+
+        let used: u32 = self.sizes.iter().map(|size| size.unwrap_or(0) as u32).sum::<u32>() + gaps;
+        let free = (total_size as u32).saturating_sub(used);
 
-        let size_from_percentage = |percentage: f32| -> f32 {
-            let clamped: f32 = percentage.clamp(0.0, 1.0);
-            total_size as f32 * clamped
+        let mut offset: u32 = 0;
+        for (index, (size, constraint)) in self.sizes.iter().zip(&self.layouts).enumerate() {
+            let start = (offset + self.free_before(index, free)).min(total_size as u32);
+            let size = (size.unwrap_or(0) as u32).min(total_size as u32 - start) as u16;
+            offset += size as u32 + self.gap as u32;
+
+            let (cross_start, cross_size) = match constraint.cross_size {
+                Some(cross_size) => {
+                    let cross_size = cross_size.min(cross_total);
+                    let cross_free = cross_total - cross_size;
+                    let cross_start = match self.align {
+                        Align::Start => 0,
+                        Align::Center => cross_free / 2,
+                        Align::End => cross_free,
+                    };
+                    (cross_start, cross_size)
+                }
+                None => (0, cross_total),
+            };
+            let start = start as u16;
+            let area = match direction {
+                Direction::Vertical => Area {
+                    x: inner_area.x + cross_start,
+                    y: inner_area.y + start,
+                    cols: cross_size,
+                    rows: size,
+                },
+                Direction::Horizontal => Area {
+                    x: inner_area.x + start,
+                    y: inner_area.y + cross_start,
+                    cols: size,
+                    rows: cross_size,
+                },
+            };
+            self.areas.push(area);
+        }
+    }
+
+    /**
+     * How much of the `free` space goes before the part at `index`, counting what went before the
+     * parts in front of it
+     */
+    fn free_before(&self, index: usize, free: u32) -> u32 {
+        let count = self.layouts.len() as u64;
+        let (index, free) = (index as u64, free as u64);
+        let before = match self.justify {
+            Justify::Start => 0,
+            Justify::Center => free / 2,
+            Justify::End => free,
+            Justify::SpaceBetween if count > 1 => free * index / (count - 1),
+            Justify::SpaceBetween => 0,
+            Justify::SpaceAround => free * (2 * index + 1) / (2 * count),
         };
+        before as u32
+    }
 
-        for item in &self.layouts {
-            match item {
-                Layout::Static(size) => statically_taken_size += size,
-                Layout::Weighted(weight) => weight_sum += *weight as f32,
+    /**
+     * Fixed parts take their size first. The flexible ones share what is left by weight, and any
+     * of them pushed past its bounds is fixed at the bound before the rest is shared again.
+     *
+     * Shares are worked out in integers. Each flexible part gets the whole cells of its share,
+     * and the cells left over go one each to the parts with the largest fractions, earlier parts
+     * first on a tie. So the flexible parts always add up to exactly the space they share.
+     */
+    fn calculate_sizes(&mut self, total_size: u16) {
+        self.sizes.clear();
+        let mut remaining = total_size as u32;
+        for constraint in &self.layouts {
+            let size = match constraint.layout {
+                Layout::Static(size) => Some(size),
                 Layout::Percentage(percentage) => {
-                    statically_taken_size += (size_from_percentage)(*percentage) as u16;
+                    Some((total_size as f32 * percentage.clamp(0.0, 1.0)) as u16)
+                }
+                Layout::Ratio(_, 0) => Some(0),
+                Layout::Ratio(numerator, denominator) => {
+                    let size = total_size as u32 * numerator as u32 / denominator as u32;
+                    Some(size.min(total_size as u32) as u16)
                 }
+                _ => None,
             }
+            // Fixed parts asking for more than is left are cut down to it
+            .map(|size| constraint.clamp(size).min(remaining as u16));
+            remaining -= size.unwrap_or(0) as u32;
+            self.sizes.push(size);
         }
 
-        let weight_unit: f32 = if weight_sum == 0.0 {
-            0.0
-        } else {
-            (total_size - statically_taken_size) as f32 / weight_sum
-        };
-        let mut offset: u16 = 0;
-
-        for item in &self.layouts {
-            let area: Area = match direction {
-                Direction::Vertical => {
-                    let x = frame_area.x;
-                    let y = frame_area.y + offset;
-                    let cols = frame_area.cols;
-                    let rows = match item {
-                        Layout::Static(size) => *size,
-                        Layout::Weighted(weight) => (*weight as f32 * weight_unit).round() as u16,
-                        Layout::Percentage(percentage) => (size_from_percentage)(*percentage) as u16,
-                    };
-                    offset += rows;
-                    Area { x, y, cols, rows }
+        loop {
+            let weight_sum: u64 = (0..self.layouts.len())
+                .filter_map(|index| self.open_weight(index))
+                .sum();
+
+            // Only one kind of violation is fixed per pass, as in flexbox: when the parts below
+            // their minimum need more than the parts above their maximum give back, the minimums
+            // are fixed, otherwise the maximums. The others may fit once the rest is shared again.
+            let total_violation: i64 = (0..self.layouts.len())
+                .filter_map(|index| self.violation(index, remaining, weight_sum))
+                .map(|(_, difference)| difference)
+                .sum();
+            // Every part is checked against the same totals, the bounds are applied afterwards
+            let fixed: Vec<(usize, u16)> = (0..self.layouts.len())
+                .filter_map(|index| {
+                    let (bound, difference) = self.violation(index, remaining, weight_sum)?;
+                    let same_kind = difference.signum() * total_violation.signum() >= 0;
+                    same_kind.then_some((index, bound))
+                })
+                .collect();
+            if !fixed.is_empty() {
+                for (index, bound) in fixed {
+                    remaining = remaining.saturating_sub(bound as u32);
+                    self.sizes[index] = Some(bound);
                 }
-                Direction::Horizontal => {
-                    let x = frame_area.x + offset;
-                    let y = frame_area.y;
-                    let rows = frame_area.rows;
-                    let cols = match item {
-                        Layout::Static(size) => *size,
-                        Layout::Weighted(weight) => (*weight as f32 * weight_unit).round() as u16,
-                        Layout::Percentage(percentage) => (size_from_percentage)(*percentage) as u16,
-                    };
-                    offset += cols;
-                    Area { x, y, cols, rows }
+                continue;
+            }
+            if weight_sum == 0 {
+                // Only zero weights are left, they get nothing
+                for size in self.sizes.iter_mut().filter(|size| size.is_none()) {
+                    *size = Some(0);
                 }
-            };
+                return;
+            }
 
-            self.areas.push(area);
+            let mut shares: Vec<(usize, u64, u64)> = (0..self.layouts.len())
+                .filter_map(|index| {
+                    let scaled_share = remaining as u64 * self.open_weight(index)?;
+                    Some((index, scaled_share / weight_sum, scaled_share % weight_sum))
+                })
+                .collect();
+            let whole_cells: u64 = shares.iter().map(|(_, cells, _)| cells).sum();
+            let left_over = (remaining as u64 - whole_cells) as usize;
+
+            // Largest fractions first, earlier parts first on a tie
+            shares.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(&b.0)));
+            for (rank, (index, cells, _)) in shares.into_iter().enumerate() {
+                let extra = u64::from(rank < left_over);
+                self.sizes[index] = Some((cells + extra) as u16);
+            }
+            return;
+        }
+    }
+
+    /**
+     * Bound the open flexible layout at `index` has to be fixed at when its share of `remaining`
+     * is out of bounds, with how much the bound differs from the share. A share is
+     * remaining * weight / weight_sum, the difference is scaled by weight_sum so nothing is
+     * divided.
+     */
+    fn violation(&self, index: usize, remaining: u32, weight_sum: u64) -> Option<(u16, i64)> {
+        let weight = self.open_weight(index)?;
+        let constraint = self.layouts[index];
+        let scaled_share = remaining as u64 * weight;
+        let bound = if scaled_share < constraint.min as u64 * weight_sum {
+            constraint.clamp(constraint.min)
+        } else if scaled_share > constraint.max as u64 * weight_sum {
+            constraint.max
+        } else {
+            return None;
+        };
+        Some((bound, (bound as u64 * weight_sum) as i64 - scaled_share as i64))
+    }
+
+    /**
+     * Weight of the flexible layout at `index` while its size is still open
+     */
+    fn open_weight(&self, index: usize) -> Option<u64> {
+        match self.sizes[index] {
+            Some(_) => None,
+            None => self.layouts[index].weight().map(u64::from),
         }
     }
 
@@ -584,4 +906,532 @@ mod test {
             }
         );
     }
+
+    fn rows(layout_buffer: &LayoutBuffer) -> Vec<u16> {
+        layout_buffer.areas().iter().map(|area| area.rows).collect()
+    }
+
+    #[test]
+    fn ratio_and_fill() {
+        let area = Area {
+            x: 2,
+            y: 3,
+            cols: 30,
+            rows: 10,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(3);
+
+        layout_buffer
+            .add_layout(Layout::Ratio(1, 3))
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Weighted(2))
+            .calculate_areas(Direction::Horizontal, &area);
+
+        assert_eq!(
+            *layout_buffer.areas(),
+            vec![
+                Area {
+                    x: 2,
+                    y: 3,
+                    cols: 10,
+                    rows: 10
+                },
+                Area {
+                    x: 12,
+                    y: 3,
+                    cols: 7,
+                    rows: 10
+                },
+                Area {
+                    x: 19,
+                    y: 3,
+                    cols: 13,
+                    rows: 10
+                },
+            ]
+        );
+
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Ratio(2, 5))
+            .add_layout(Layout::Ratio(3, 0))
+            .add_layout(Layout::Ratio(7, 5))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![4, 0, 6]);
+    }
+
+    #[test]
+    fn min_and_max() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 10,
+            rows: 30,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(3);
+
+        layout_buffer
+            .add_layout(Layout::Min(20))
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![20, 5, 5]);
+
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Max(4))
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Static(6))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![4, 20, 6]);
+
+        // Bounds that are not hit change nothing
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Min(2))
+            .add_layout(Layout::Max(25))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![15, 15]);
+    }
+
+    #[test]
+    fn bounded_layouts() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 100,
+            rows: 40,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(4);
+
+        layout_buffer
+            .add_layout(Layout::Weighted(1).with_min(10))
+            .add_layout(Layout::Weighted(9))
+            .add_layout(Layout::Percentage(0.5).with_min(1).with_max(30))
+            .calculate_areas(Direction::Horizontal, &area);
+        assert_eq!(
+            layout_buffer.areas().iter().map(|area| area.cols).collect::<Vec<u16>>(),
+            vec![10, 60, 30]
+        );
+
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Static(2).with_min(5))
+            .add_layout(Layout::Weighted(1).with_min(3).with_max(8))
+            .add_layout(Layout::Weighted(1))
+            .add_layout(Layout::Weighted(0).with_min(4))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![5, 8, 23, 4]);
+    }
+
+    #[test]
+    fn areas_never_exceed_the_frame() {
+        let area = Area {
+            x: 5,
+            y: 5,
+            cols: 10,
+            rows: 12,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(4);
+
+        layout_buffer
+            .add_layout(Layout::Static(5))
+            .add_layout(Layout::Min(6))
+            .add_layout(Layout::Static(4))
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &area);
+
+        assert_eq!(rows(&layout_buffer), vec![5, 6, 1, 0]);
+        let last = layout_buffer.area(3).unwrap();
+        assert_eq!(last.y, 17);
+        assert!(layout_buffer
+            .areas()
+            .iter()
+            .all(|part| part.y + part.rows <= area.y + area.rows));
+    }
+
+    #[test]
+    fn remainder_goes_to_the_largest_fractions() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 10,
+            rows: 10,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(4);
+
+        layout_buffer
+            .add_layout(Layout::Weighted(1))
+            .add_layout(Layout::Weighted(2))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![3, 7]);
+
+        // Equal fractions, the earlier parts get the extra cells
+        layout_buffer.clear();
+        for _ in 0..4 {
+            layout_buffer.add_layout(Layout::Fill);
+        }
+        layout_buffer.calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![3, 3, 2, 2]);
+
+        // Rounding every share on its own would give 4 + 4 + 4 = 12
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Static(1))
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![1, 3, 3, 3]);
+    }
+
+    #[test]
+    fn overflowing_statics_are_clamped() {
+        let area = Area {
+            x: 0,
+            y: u16::MAX - 10,
+            cols: 1,
+            rows: 10,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(4);
+
+        layout_buffer
+            .add_layout(Layout::Static(u16::MAX))
+            .add_layout(Layout::Static(u16::MAX))
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![10, 0, 0]);
+        assert_eq!(layout_buffer.area(2).unwrap().y, u16::MAX);
+    }
+
+    #[test]
+    fn gap_and_margin() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 20,
+            rows: 10,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(3)
+            .with_gap(2)
+            .with_margin(Padding::from(1, 0, 3, 1));
+
+        layout_buffer
+            .add_layout(Layout::Static(4))
+            .add_layout(Layout::Fill)
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Horizontal, &area);
+        assert_eq!(
+            *layout_buffer.areas(),
+            vec![
+                Area {
+                    x: 3,
+                    y: 1,
+                    cols: 4,
+                    rows: 9
+                },
+                Area {
+                    x: 9,
+                    y: 1,
+                    cols: 4,
+                    rows: 9
+                },
+                Area {
+                    x: 15,
+                    y: 1,
+                    cols: 4,
+                    rows: 9
+                },
+            ]
+        );
+
+        // The gaps are taken first, the parts get cut short to what they leave
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Static(6))
+            .add_layout(Layout::Static(6))
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![5, 0, 0]);
+        assert_eq!(layout_buffer.area(1).unwrap().y, 8);
+        assert_eq!(layout_buffer.area(2).unwrap().y, 10);
+    }
+
+    #[test]
+    fn justify() {
+        let area = Area {
+            x: 10,
+            y: 0,
+            cols: 10,
+            rows: 1,
+        };
+        let starts = |justify: Justify| {
+            let mut layout_buffer = LayoutBuffer::with_capacity(3).with_justify(justify);
+            layout_buffer
+                .add_layout(Layout::Static(1))
+                .add_layout(Layout::Static(2))
+                .add_layout(Layout::Static(1))
+                .calculate_areas(Direction::Horizontal, &area);
+            layout_buffer.areas().iter().map(|area| area.x).collect::<Vec<u16>>()
+        };
+
+        assert_eq!(starts(Justify::Start), vec![10, 11, 13]);
+        assert_eq!(starts(Justify::Center), vec![13, 14, 16]);
+        assert_eq!(starts(Justify::End), vec![16, 17, 19]);
+        assert_eq!(starts(Justify::SpaceBetween), vec![10, 14, 19]);
+        assert_eq!(starts(Justify::SpaceAround), vec![11, 14, 18]);
+
+        // Flexible parts leave nothing free to justify
+        let mut layout_buffer = LayoutBuffer::with_capacity(2).with_justify(Justify::End);
+        layout_buffer
+            .add_layout(Layout::Static(3))
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Horizontal, &area);
+        assert_eq!(layout_buffer.area(0).unwrap().x, 10);
+    }
+
+    #[test]
+    fn align_fixed_cross_sizes() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 11,
+            rows: 6,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(3)
+            .with_justify(Justify::Center)
+            .with_align(Align::Center);
+
+        layout_buffer
+            .add_layout(Layout::Static(2).with_cross_size(5))
+            .add_layout(Layout::Static(1))
+            .add_layout(Layout::Static(1).with_cross_size(20))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(
+            *layout_buffer.areas(),
+            vec![
+                Area {
+                    x: 3,
+                    y: 1,
+                    cols: 5,
+                    rows: 2
+                },
+                Area {
+                    x: 0,
+                    y: 3,
+                    cols: 11,
+                    rows: 1
+                },
+                Area {
+                    x: 0,
+                    y: 4,
+                    cols: 11,
+                    rows: 1
+                },
+            ]
+        );
+
+        let mut layout_buffer = LayoutBuffer::with_capacity(1).with_align(Align::End);
+        layout_buffer
+            .add_layout(Layout::Fill.with_cross_size(2))
+            .calculate_areas(Direction::Horizontal, &area);
+        assert_eq!(
+            *layout_buffer.area(0).unwrap(),
+            Area {
+                x: 0,
+                y: 4,
+                cols: 11,
+                rows: 2
+            }
+        );
+    }
+
+    #[test]
+    fn one_kind_of_violation_per_pass() {
+        let area = Area {
+            x: 0,
+            y: 0,
+            cols: 1,
+            rows: 12,
+        };
+        let mut layout_buffer = LayoutBuffer::with_capacity(3);
+
+        // Once the first part is capped, the others share all of it and the minimum is met
+        layout_buffer
+            .add_layout(Layout::Weighted(10).with_min(0).with_max(0))
+            .add_layout(Layout::Weighted(1).with_min(5))
+            .add_layout(Layout::Weighted(1))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![0, 6, 6]);
+
+        // The minimum needs more than the maximum gives back, capping first would overflow
+        layout_buffer.clear();
+        layout_buffer
+            .add_layout(Layout::Max(5))
+            .add_layout(Layout::Min(10))
+            .calculate_areas(Direction::Vertical, &area);
+        assert_eq!(rows(&layout_buffer), vec![2, 10]);
+    }
+
+    /**
+     * Xorshift generator, enough to make up layouts without pulling in a crate
+     */
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, bound: u64) -> u16 {
+            (self.next() % bound) as u16
+        }
+
+        fn size(&mut self) -> u16 {
+            match self.below(4) {
+                0 => u16::MAX - self.below(3),
+                _ => self.below(300),
+            }
+        }
+
+        fn constraint(&mut self) -> Constraint {
+            let layout = match self.below(7) {
+                0 => Layout::Static(self.size()),
+                1 => Layout::Weighted(self.below(10)),
+                2 => Layout::Percentage(self.below(120) as f32 / 100.0),
+                3 => Layout::Ratio(self.below(5), self.below(5)),
+                4 => Layout::Min(self.size()),
+                5 => Layout::Max(self.size()),
+                _ => Layout::Fill,
+            };
+            let mut constraint = Constraint::from(layout);
+            if self.below(4) == 0 {
+                constraint = constraint.with_min(self.size());
+            }
+            if self.below(4) == 0 {
+                constraint = constraint.with_max(self.size());
+            }
+            if self.below(4) == 0 {
+                constraint = constraint.with_cross_size(self.size());
+            }
+            constraint
+        }
+
+        fn layout_buffer(&mut self) -> LayoutBuffer {
+            let justify = [
+                Justify::Start,
+                Justify::Center,
+                Justify::End,
+                Justify::SpaceBetween,
+                Justify::SpaceAround,
+            ][self.below(5) as usize];
+            let align = [Align::Start, Align::Center, Align::End][self.below(3) as usize];
+            let margin = Padding::from(self.size(), self.size(), self.size(), self.size());
+            LayoutBuffer::with_capacity(8)
+                .with_gap(match self.below(3) {
+                    0 => 0,
+                    1 => self.below(4),
+                    _ => self.size(),
+                })
+                .with_margin(margin)
+                .with_justify(justify)
+                .with_align(align)
+        }
+    }
+
+    #[test]
+    fn random_layouts_stay_inside_the_frame() {
+        let mut random = Random(0x2545_f491_4f6c_dd1d);
+
+        for _ in 0..2000 {
+            let frame_area = Area {
+                x: random.size(),
+                y: random.size(),
+                cols: random.size(),
+                rows: random.size(),
+            };
+            // Keep the frame itself addressable
+            let frame_area = Area {
+                cols: frame_area.cols.min(u16::MAX - frame_area.x),
+                rows: frame_area.rows.min(u16::MAX - frame_area.y),
+                ..frame_area
+            };
+            let vertical = random.below(2) == 0;
+            // Plain buffers half of the time, so their parts can be checked to follow each other
+            let plain = random.below(2) == 0;
+            let mut layout_buffer = if plain {
+                LayoutBuffer::with_capacity(8)
+            } else {
+                random.layout_buffer()
+            };
+            for _ in 0..random.below(8) {
+                layout_buffer.add_layout(random.constraint());
+            }
+            if vertical {
+                layout_buffer.calculate_areas(Direction::Vertical, &frame_area);
+            } else {
+                layout_buffer.calculate_areas(Direction::Horizontal, &frame_area);
+            }
+
+            let (start, total) = if vertical {
+                (frame_area.y, frame_area.rows)
+            } else {
+                (frame_area.x, frame_area.cols)
+            };
+            let mut offset = start as u32;
+            for area in layout_buffer.areas() {
+                let (position, size) = if vertical {
+                    (area.y, area.rows)
+                } else {
+                    (area.x, area.cols)
+                };
+                if plain {
+                    assert_eq!(position as u32, offset, "{:?}", layout_buffer.layouts);
+                } else {
+                    assert!(position as u32 >= offset, "{:?}", layout_buffer.layouts);
+                }
+                offset = position as u32 + size as u32;
+                assert!(area.x >= frame_area.x && area.y >= frame_area.y);
+                assert!(
+                    area.x as u32 + area.cols as u32
+                        <= frame_area.x as u32 + frame_area.cols as u32
+                );
+                assert!(
+                    area.y as u32 + area.rows as u32
+                        <= frame_area.y as u32 + frame_area.rows as u32
+                );
+            }
+            assert!(offset <= start as u32 + total as u32);
+        }
+    }
+
+    #[test]
+    fn random_weights_fill_the_frame_exactly() {
+        let mut random = Random(0x9e37_79b9_7f4a_7c15);
+        let mut layout_buffer = LayoutBuffer::with_capacity(8);
+
+        for _ in 0..2000 {
+            let frame_area = Area {
+                x: 0,
+                y: 0,
+                cols: random.size(),
+                rows: 1,
+            };
+            layout_buffer.clear();
+            for _ in 0..1 + random.below(8) {
+                layout_buffer.add_layout(Layout::Weighted(1 + random.below(20)));
+            }
+            layout_buffer.calculate_areas(Direction::Horizontal, &frame_area);
+
+            let total: u32 = layout_buffer
+                .areas()
+                .iter()
+                .map(|area| area.cols as u32)
+                .sum();
+            assert_eq!(total, frame_area.cols as u32);
+        }
+    }
 }
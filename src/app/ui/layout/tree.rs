@@ -0,0 +1,303 @@
+use std::collections::HashMap;
+
+use crate::app::renderer::Area;
+
+use super::{Align, Constraint, Direction, Justify, LayoutBuffer, Padding};
+
+/**
+ * One named part of a `LayoutTree`. Its constraint sizes it within its parent, its children split
+ * its area along its direction, e.g.
+ *
+ * ```ignore
+ * LayoutNode::from("root", Layout::Fill)
+ *     .with_child(LayoutNode::from("title", Layout::Static(1)))
+ *     .with_child(
+ *         LayoutNode::from("columns", Layout::Fill)
+ *             .with_direction(Direction::Horizontal)
+ *             .with_child(LayoutNode::from("left", Layout::Fill))
+ *             .with_child(LayoutNode::from("right", Layout::Fill)),
+ *     )
+ * ```
+ */
+pub struct LayoutNode {
+    name: String,
+    constraint: Constraint,
+    direction: Direction,
+    hidden: bool,
+    children: Vec<LayoutNode>,
+    /**
+     * Splits the area among the children, keeps the spacing and alignment of this node
+     */
+    layout_buffer: LayoutBuffer,
+}
+
+impl LayoutNode {
+    /**
+     * Node without children that splits along `Direction::Vertical` once it has some
+     */
+    pub fn from(name: impl Into<String>, constraint: impl Into<Constraint>) -> Self {
+        Self {
+            name: name.into(),
+            constraint: constraint.into(),
+            direction: Direction::Vertical,
+            hidden: false,
+            children: Vec::new(),
+            layout_buffer: LayoutBuffer::with_capacity(0),
+        }
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_child(mut self, child: LayoutNode) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /**
+     * See `LayoutBuffer::with_gap`
+     */
+    pub fn with_gap(mut self, gap: u16) -> Self {
+        self.layout_buffer = self.layout_buffer.with_gap(gap);
+        self
+    }
+
+    /**
+     * See `LayoutBuffer::with_margin`
+     */
+    // No view pads, centers or aligns the children of a node yet
+    #[allow(dead_code)]
+    pub fn with_margin(mut self, margin: Padding) -> Self {
+        self.layout_buffer = self.layout_buffer.with_margin(margin);
+        self
+    }
+
+    /**
+     * See `LayoutBuffer::with_justify`
+     */
+    #[allow(dead_code)]
+    pub fn with_justify(mut self, justify: Justify) -> Self {
+        self.layout_buffer = self.layout_buffer.with_justify(justify);
+        self
+    }
+
+    /**
+     * See `LayoutBuffer::with_align`
+     */
+    #[allow(dead_code)]
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.layout_buffer = self.layout_buffer.with_align(align);
+        self
+    }
+
+    /**
+     * Names of this node and of every node below it, in tree order
+     */
+    fn names<'a>(&'a self, names: &mut Vec<&'a str>) {
+        names.push(&self.name);
+        for child in &self.children {
+            child.names(names);
+        }
+    }
+
+    fn find_mut(&mut self, name: &str) -> Option<&mut LayoutNode> {
+        if self.name == name {
+            return Some(self);
+        }
+        self.children.iter_mut().find_map(|child| child.find_mut(name))
+    }
+
+    /**
+     * Split `area` among the visible children and record where each of them ends up
+     */
+    fn calculate_areas(&mut self, area: &Area, areas: &mut HashMap<String, Area>) {
+        self.layout_buffer.clear();
+        for child in self.children.iter().filter(|child| !child.hidden) {
+            self.layout_buffer.add_layout(child.constraint);
+        }
+        self.layout_buffer.calculate_areas(self.direction, area);
+
+        let visible_children = self.children.iter_mut().filter(|child| !child.hidden);
+        for (child, child_area) in visible_children.zip(self.layout_buffer.areas()) {
+            areas.insert(child.name.clone(), *child_area);
+            child.calculate_areas(child_area, areas);
+        }
+    }
+}
+
+/**
+ * Layout of a whole screen described as a tree of named nodes, solved in one pass to an area per
+ * node. Names must be unique, they are how nodes are hidden and their areas found.
+ */
+pub struct LayoutTree {
+    root: LayoutNode,
+    areas: HashMap<String, Area>,
+}
+
+impl LayoutTree {
+    /**
+     * Panics if two nodes have the same name
+     */
+    pub fn from(root: LayoutNode) -> Self {
+        let mut names = Vec::new();
+        root.names(&mut names);
+        names.sort_unstable();
+        assert!(
+            names.windows(2).all(|pair| pair[0] != pair[1]),
+            "layout node names are not unique: {names:?}"
+        );
+        Self {
+            root,
+            areas: HashMap::new(),
+        }
+    }
+
+    /**
+     * A hidden node and everything below it take no space and have no area, the nodes next to it
+     * share the space as if it were not there. Unknown names are ignored.
+     */
+    pub fn set_hidden(&mut self, name: &str, hidden: bool) {
+        if let Some(node) = self.root.find_mut(name) {
+            node.hidden = hidden;
+        }
+    }
+
+    /**
+     * Solve the tree with the root covering `frame_area`, its constraint is not used
+     */
+    pub fn calculate_areas(&mut self, frame_area: &Area) {
+        self.areas.clear();
+        self.areas.insert(self.root.name.clone(), *frame_area);
+        self.root.calculate_areas(frame_area, &mut self.areas);
+    }
+
+    /**
+     * Area of the node called `name` from the last calculation, `None` for unknown or hidden nodes
+     */
+    pub fn area(&self, name: &str) -> Option<&Area> {
+        self.areas.get(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::ui::Layout;
+
+    const FRAME: Area = Area {
+        x: 0,
+        y: 0,
+        cols: 20,
+        rows: 10,
+    };
+
+    fn screen() -> LayoutTree {
+        LayoutTree::from(
+            LayoutNode::from("root", Layout::Fill)
+                .with_child(LayoutNode::from("title", Layout::Static(1)))
+                .with_child(LayoutNode::from("notice", Layout::Static(2)))
+                .with_child(
+                    LayoutNode::from("columns", Layout::Fill)
+                        .with_direction(Direction::Horizontal)
+                        .with_gap(2)
+                        .with_child(LayoutNode::from("left", Layout::Static(6)))
+                        .with_child(LayoutNode::from("right", Layout::Fill)),
+                ),
+        )
+    }
+
+    #[test]
+    fn nodes_are_found_by_name() {
+        let mut tree = screen();
+        tree.calculate_areas(&FRAME);
+
+        assert_eq!(*tree.area("root").unwrap(), FRAME);
+        assert_eq!(
+            *tree.area("title").unwrap(),
+            Area {
+                x: 0,
+                y: 0,
+                cols: 20,
+                rows: 1
+            }
+        );
+        assert_eq!(
+            *tree.area("right").unwrap(),
+            Area {
+                x: 8,
+                y: 3,
+                cols: 12,
+                rows: 7
+            }
+        );
+        assert!(tree.area("missing").is_none());
+    }
+
+    #[test]
+    fn hidden_nodes_take_no_space() {
+        let mut tree = screen();
+        tree.set_hidden("notice", true);
+        tree.calculate_areas(&FRAME);
+
+        assert!(tree.area("notice").is_none());
+        assert_eq!(tree.area("columns").unwrap().y, 1);
+        assert_eq!(tree.area("left").unwrap().rows, 9);
+
+        // Everything below a hidden node is gone as well
+        tree.set_hidden("notice", false);
+        tree.set_hidden("columns", true);
+        tree.calculate_areas(&FRAME);
+        assert_eq!(tree.area("notice").unwrap().rows, 2);
+        assert!(tree.area("left").is_none());
+    }
+
+    #[test]
+    fn nodes_space_and_align_their_children() {
+        let mut tree = LayoutTree::from(
+            LayoutNode::from("root", Layout::Fill)
+                .with_margin(Padding::high_and_wide(1, 2))
+                .with_justify(Justify::Center)
+                .with_child(LayoutNode::from("title", Layout::Static(2)))
+                .with_child(
+                    LayoutNode::from("buttons", Layout::Static(3))
+                        .with_direction(Direction::Horizontal)
+                        .with_justify(Justify::End)
+                        .with_align(Align::End)
+                        .with_child(LayoutNode::from("ok", Layout::Static(4).with_cross_size(1))),
+                ),
+        );
+        tree.calculate_areas(&FRAME);
+
+        // 8 rows inside the margin, the 5 taken are centered
+        assert_eq!(
+            *tree.area("title").unwrap(),
+            Area {
+                x: 2,
+                y: 2,
+                cols: 16,
+                rows: 2
+            }
+        );
+        assert_eq!(
+            *tree.area("ok").unwrap(),
+            Area {
+                x: 14,
+                y: 6,
+                cols: 4,
+                rows: 1
+            }
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "layout node names are not unique")]
+    fn names_are_unique() {
+        LayoutTree::from(
+            LayoutNode::from("root", Layout::Fill)
+                .with_child(LayoutNode::from("title", Layout::Static(1)))
+                .with_child(LayoutNode::from("body", Layout::Fill).with_child(LayoutNode::from("title", Layout::Fill))),
+        );
+    }
+}
@@ -1,24 +1,72 @@
+use crate::app::renderer::Area;
+
+use super::{Role, Theme};
+
 pub struct Style {
     pub borders: Borders,
-    pub background: RGBA,
-    pub foreground: RGBA,
+    pub background: Color,
+    pub foreground: Color,
 }
 
 impl Style {
+    /**
+     * Text in the theme's text color on whatever is below
+     */
     pub fn new() -> Self {
-        Self::from(Borders::none(), RGBA::transparent(), RGBA::transparent())
+        Self::from(Borders::none(), Color::Inherit, Role::Text)
     }
 
-    pub fn from(borders: Borders, background: RGBA, foreground: RGBA) -> Self {
+    pub fn from(borders: Borders, background: impl Into<Color>, foreground: impl Into<Color>) -> Self {
         Self {
             borders,
-            background,
-            foreground,
+            background: background.into(),
+            foreground: foreground.into(),
         }
     }
 
+    /**
+     * Surface with a border all around
+     */
     pub fn bordered() -> Self {
-        Self::from(Borders::all(), RGBA::new(), RGBA::white())
+        Self::from(Borders::all(), Role::Surface, Role::Border)
+    }
+}
+
+/**
+ * Either a fixed color or a role that is looked up in the theme while rendering
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Color {
+    /**
+     * Leave whatever was drawn below as it is
+     */
+    Inherit,
+    Rgba(RGBA),
+    Role(Role),
+}
+
+impl Color {
+    /**
+     * `Inherit` becomes fully transparent, which blends into anything as nothing
+     */
+    pub fn resolve(&self, theme: &Theme) -> RGBA {
+        match self {
+            Self::Inherit => RGBA::transparent(),
+            Self::Rgba(color) => *color,
+            Self::Role(role) => theme.get(*role),
+        }
+    }
+}
+
+impl From<RGBA> for Color {
+    fn from(value: RGBA) -> Self {
+        Self::Rgba(value)
+    }
+}
+
+impl From<Role> for Color {
+    fn from(value: Role) -> Self {
+        Self::Role(value)
     }
 }
 
@@ -49,20 +97,39 @@ impl Borders {
     }
 }
 
-#[derive(Copy, Clone)]
+/**
+ * Color with straight, not premultiplied, alpha: 0 is fully transparent and 255 opaque
+ */
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
 pub struct RGBA {
     pub red: u8,
     pub green: u8,
     pub blue: u8,
-    pub alpha: bool,
+    pub alpha: u8,
 }
 
-impl RGBA {
-    pub fn new() -> Self {
-        Self::transparent()
-    }
+/**
+ * Names accepted by `RGBA::parse`, with their CSS values
+ */
+const NAMED_COLORS: [(&str, RGBA); 13] = [
+    ("black", RGBA::rgb(0, 0, 0)),
+    ("white", RGBA::rgb(255, 255, 255)),
+    ("gray", RGBA::rgb(128, 128, 128)),
+    ("grey", RGBA::rgb(128, 128, 128)),
+    ("red", RGBA::rgb(255, 0, 0)),
+    ("green", RGBA::rgb(0, 128, 0)),
+    ("blue", RGBA::rgb(0, 0, 255)),
+    ("yellow", RGBA::rgb(255, 255, 0)),
+    ("cyan", RGBA::rgb(0, 255, 255)),
+    ("magenta", RGBA::rgb(255, 0, 255)),
+    ("orange", RGBA::rgb(255, 165, 0)),
+    ("purple", RGBA::rgb(128, 0, 128)),
+    ("transparent", RGBA::from(0, 0, 0, 0)),
+];
 
-    pub fn from(red: u8, green: u8, blue: u8, alpha: bool) -> Self {
+impl RGBA {
+    pub const fn from(red: u8, green: u8, blue: u8, alpha: u8) -> Self {
         Self {
             red,
             green,
@@ -71,39 +138,60 @@ impl RGBA {
         }
     }
 
-    pub fn black() -> Self {
-        Self::from(0, 0, 0, false)
-    }
-
-    pub fn white() -> Self {
-        Self::from(255, 255, 255, false)
-    }
-
-    pub fn red() -> Self {
-        Self::from(255, 0, 0, false)
+    /**
+     * Opaque color
+     */
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self::from(red, green, blue, u8::MAX)
     }
 
-    pub fn green() -> Self {
-        Self::from(0, 255, 0, false)
+    pub fn transparent() -> Self {
+        Self::from(0, 0, 0, 0)
     }
 
-    pub fn blue() -> Self {
-        Self::from(0, 0, 255, false)
+    pub fn is_transparent(&self) -> bool {
+        self.alpha == 0
     }
 
-    pub fn transparent() -> Self {
-        Self::from(0, 0, 0, true)
+    /**
+     * This color painted over `below`, the usual "source over" compositing
+     */
+    pub fn over(self, below: RGBA) -> RGBA {
+        match self.alpha {
+            u8::MAX => return self,
+            0 => return below,
+            _ => {}
+        }
+        let top_alpha = self.alpha as u32;
+        let below_alpha = below.alpha as u32 * (255 - top_alpha) / 255;
+        let alpha = top_alpha + below_alpha;
+        let mix = |top: u8, bottom: u8| {
+            ((top as u32 * top_alpha + bottom as u32 * below_alpha + alpha / 2) / alpha) as u8
+        };
+        Self::from(
+            mix(self.red, below.red),
+            mix(self.green, below.green),
+            mix(self.blue, below.blue),
+            alpha as u8,
+        )
     }
 
-    /*
-     * Pack an RGBA value into a u32 with big endianness
+    /**
+     * Color written as `#rrggbb`, `#rrggbbaa` or one of a few CSS names such as `red`
      */
-    pub fn as_u32(&self) -> u32 {
-        let alpha_numeric_value: u32 = if self.alpha { 0 } else { 255 };
-        alpha_numeric_value << 24
-            | (self.red as u32) << 16
-            | (self.green as u32) << 8
-            | (self.blue as u32)
+    pub fn parse(text: &str) -> Option<Self> {
+        let Some(hex) = text.strip_prefix('#') else {
+            return NAMED_COLORS
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(text))
+                .map(|(_, color)| *color);
+        };
+        if !hex.is_ascii() || (hex.len() != 6 && hex.len() != 8) {
+            return None;
+        }
+        let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+        let alpha = if hex.len() == 8 { channel(6)? } else { u8::MAX };
+        Some(Self::from(channel(0)?, channel(2)?, channel(4)?, alpha))
     }
 }
 
@@ -135,4 +223,64 @@ impl Padding {
     pub fn high_and_wide(high: u16, wide: u16) -> Self {
         Self::from(high, high, wide, wide)
     }
+
+    /**
+     * What is left of `area` inside this padding, which may be nothing
+     */
+    pub fn inside(&self, area: Area) -> Area {
+        Area {
+            x: area.x.saturating_add(self.left.min(area.cols)),
+            y: area.y.saturating_add(self.top.min(area.rows)),
+            cols: area.cols.saturating_sub(self.left).saturating_sub(self.right),
+            rows: area.rows.saturating_sub(self.top).saturating_sub(self.bottom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(RGBA::parse("#ff8000"), Some(RGBA::rgb(255, 128, 0)));
+        assert_eq!(RGBA::parse("#FF8000"), Some(RGBA::rgb(255, 128, 0)));
+        assert_eq!(RGBA::parse("#ff800080"), Some(RGBA::from(255, 128, 0, 128)));
+        assert_eq!(RGBA::parse("ff8000"), None);
+        assert_eq!(RGBA::parse("#ff800"), None);
+        assert_eq!(RGBA::parse("#ff80000"), None);
+        assert_eq!(RGBA::parse("#ff800g"), None);
+        assert_eq!(RGBA::parse("#ff80é"), None);
+    }
+
+    #[test]
+    fn parse_names() {
+        assert_eq!(RGBA::parse("orange"), Some(RGBA::rgb(255, 165, 0)));
+        assert_eq!(RGBA::parse("Grey"), RGBA::parse("gray"));
+        assert_eq!(RGBA::parse("transparent"), Some(RGBA::transparent()));
+        assert_eq!(RGBA::parse("dark red"), None);
+    }
+
+    #[test]
+    fn compositing() {
+        let below = RGBA::rgb(0, 0, 200);
+
+        assert_eq!(RGBA::rgb(255, 0, 0).over(below), RGBA::rgb(255, 0, 0));
+        assert_eq!(RGBA::transparent().over(below), below);
+        assert_eq!(RGBA::from(255, 0, 0, 128).over(below), RGBA::rgb(128, 0, 100));
+        // Two half transparent layers over nothing stay partly transparent
+        let layered = RGBA::from(255, 0, 0, 128).over(RGBA::from(0, 0, 255, 128));
+        assert_eq!(layered, RGBA::from(171, 0, 84, 191));
+        // Transparent black is not black
+        assert_eq!(RGBA::from(0, 0, 0, 0).over(RGBA::rgb(9, 9, 9)), RGBA::rgb(9, 9, 9));
+    }
+
+    #[test]
+    fn roles_resolve_through_the_theme() {
+        let theme = Theme::by_name("light").unwrap();
+
+        assert_eq!(Color::from(Role::Accent).resolve(&theme), theme.accent);
+        assert_eq!(Color::from(RGBA::rgb(255, 0, 0)).resolve(&theme), RGBA::rgb(255, 0, 0));
+        assert!(Color::Inherit.resolve(&theme).is_transparent());
+    }
 }
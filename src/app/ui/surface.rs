@@ -1,6 +1,6 @@
 use crate::app::{
-    renderer::{Area, Renderer},
-    ui::Padding,
+    renderer::{Area, Renderer, Widget},
+    ui::{Padding, Style},
 };
 
 pub struct Surface<F>
@@ -16,14 +16,20 @@ impl<F: SurfaceOnRender> Surface<F> {
         Self { on_render, padding }
     }
 
+    /**
+     * The content gets what the padding leaves of `area`, which may be nothing
+     */
     pub fn render_content(&self, renderer: &mut dyn Renderer, area: Area) {
-        let content_area = Area {
-            x: area.x + self.padding.left,
-            y: area.y + self.padding.top,
-            cols: area.cols - self.padding.left - self.padding.right,
-            rows: area.rows - self.padding.top - self.padding.bottom,
-        };
-        (self.on_render)(renderer, &content_area);
+        (self.on_render)(renderer, &self.padding.inside(area));
+    }
+}
+
+impl<F: SurfaceOnRender> Widget for Surface<F> {
+    fn render(&mut self, renderer: &mut dyn Renderer, area: Area, style: &Style) {
+        let background = renderer.resolve(style.background);
+        renderer.fill_rect(area, background);
+        renderer.draw_border(area, style);
+        self.render_content(renderer, area);
     }
 }
 
@@ -1,96 +1,174 @@
 use crate::app::renderer::Area;
 
-use super::{Direction, Layout, LayoutBuffer};
+use super::{Constraint, Direction, LayoutBuffer};
 
+/**
+ * Grid of cells where every row shares the same columns, so they line up from one row to the
+ * next. Columns and rows are sized like the parts of a `LayoutBuffer`, bounds included, and a
+ * cell can span several of them, see `add_span`.
+ */
 pub struct Table {
-    vertical_layout_buf: LayoutBuffer,
-    rows_layout_bufs: Vec<LayoutBuffer>,
-    row_count: usize,
+    columns: LayoutBuffer,
+    rows: LayoutBuffer,
+    spans: Vec<Span>,
+}
+
+/**
+ * Cells merged into one, from (col, row) over `cols` columns and `rows` rows
+ */
+#[derive(Copy, Clone)]
+struct Span {
+    col: usize,
+    row: usize,
+    cols: usize,
+    rows: usize,
+}
+
+impl Span {
+    fn is_empty(&self) -> bool {
+        self.cols == 0 || self.rows == 0
+    }
+
+    fn covers(&self, col: usize, row: usize) -> bool {
+        (self.col..self.col + self.cols).contains(&col) && (self.row..self.row + self.rows).contains(&row)
+    }
+
+    /**
+     * Empty spans cover no cell, so they overlap nothing
+     */
+    fn overlaps(&self, other: &Span) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && self.col < other.col + other.cols
+            && other.col < self.col + self.cols
+            && self.row < other.row + other.rows
+            && other.row < self.row + self.rows
+    }
 }
 
 impl Table {
     pub fn with_capacity(cols: usize, rows: usize) -> Self {
-        let mut rows_layout_bufs = Vec::with_capacity(rows);
-        for _ in 0..rows {
-            let buf = LayoutBuffer::with_capacity(cols);
-            rows_layout_bufs.push(buf);
-        }
-
         Self {
-            vertical_layout_buf: LayoutBuffer::with_capacity(rows),
-            rows_layout_bufs,
-            row_count: 0,
+            columns: LayoutBuffer::with_capacity(cols),
+            rows: LayoutBuffer::with_capacity(rows),
+            spans: Vec::new(),
         }
     }
 
-    pub fn add_row(&mut self, row_layout: Layout, formatter: &dyn Fn(&mut LayoutBuffer)) -> &mut Self {
-        self.vertical_layout_buf.add_layout(row_layout);
-        formatter(self.rows_layout_bufs.get_mut(self.row_count).unwrap());
-        self.row_count += 1;
+    pub fn add_column(&mut self, column_layout: impl Into<Constraint>) -> &mut Self {
+        self.columns.add_layout(column_layout);
         self
     }
 
-    pub fn calculate_areas(&mut self, area: &Area) {
-        self.vertical_layout_buf
-            .calculate_areas(Direction::Vertical, area);
-
-        let mut i: usize = 0;
-        for row_area in self.vertical_layout_buf.areas() {
-            self.rows_layout_bufs
-                .get_mut(i)
-                .unwrap()
-                .calculate_areas(Direction::Horizontal, row_area);
-            i += 1;
+    pub fn add_row(&mut self, row_layout: impl Into<Constraint>) -> &mut Self {
+        self.rows.add_layout(row_layout);
+        self
+    }
+
+    /**
+     * Merge the cells from (col, row) over `cols` columns and `rows` rows into one. A span reaching
+     * past the last column or row is cut at it, an empty one is ignored.
+     *
+     * Panics if the span overlaps a span added before.
+     */
+    pub fn add_span(&mut self, col: usize, row: usize, cols: usize, rows: usize) -> &mut Self {
+        let span = Span {
+            col,
+            row,
+            cols,
+            rows,
+        };
+        if span.is_empty() {
+            return self;
         }
+        assert!(
+            !self.spans.iter().any(|other| other.overlaps(&span)),
+            "span at ({col}, {row}) overlaps a span added before"
+        );
+        self.spans.push(span);
+        self
     }
 
-    pub fn area(&self, col: usize, row: usize) -> Area {
-        *self.rows_layout_bufs.get(row).unwrap().area(col).unwrap()
+    pub fn calculate_areas(&mut self, area: &Area) {
+        self.columns.calculate_areas(Direction::Horizontal, area);
+        self.rows.calculate_areas(Direction::Vertical, area);
     }
 
-    pub fn row_area(&self, row: usize) -> Area {
-        *self.vertical_layout_buf.area(row).unwrap()
+    /**
+     * Area of the cell at (col, row), the whole span when the cell is part of one. `None` for a
+     * cell past the last column or row, e.g. of an empty table or before the first calculation.
+     */
+    pub fn area(&self, col: usize, row: usize) -> Option<Area> {
+        let span = self.span_at(col, row).unwrap_or(Span {
+            col,
+            row,
+            cols: 1,
+            rows: 1,
+        });
+        let first_column = self.columns.area(span.col)?;
+        let first_row = self.rows.area(span.row)?;
+        // Spans are cut at the last column and row, there is one since the first cell exists
+        let last_column = self.columns.area((span.col + span.cols).min(self.columns.areas().len()) - 1)?;
+        let last_row = self.rows.area((span.row + span.rows).min(self.rows.areas().len()) - 1)?;
+        Some(Area {
+            x: first_column.x,
+            y: first_row.y,
+            cols: last_column.x + last_column.cols - first_column.x,
+            rows: last_row.y + last_row.rows - first_row.y,
+        })
     }
 
-    pub fn clear(&mut self) {
-        self.row_count = 0;
-        self.vertical_layout_buf.clear();
-        for layout in &mut self.rows_layout_bufs {
-            layout.clear();
+    /**
+     * Find the (col, row) of the cell containing the given screen position, if any. Inside a span
+     * that is the cell the span starts at.
+     */
+    pub fn cell_at(&self, x: u16, y: u16) -> Option<(usize, usize)> {
+        let col = self.columns.areas().iter().position(|area| area.contains(x, y))?;
+        let row = self.rows.areas().iter().position(|area| area.contains(x, y))?;
+        match self.span_at(col, row) {
+            Some(span) => Some((span.col, span.row)),
+            None => Some((col, row)),
         }
     }
+
+    pub fn clear(&mut self) {
+        self.columns.clear();
+        self.rows.clear();
+        self.spans.clear();
+    }
+
+    fn span_at(&self, col: usize, row: usize) -> Option<Span> {
+        self.spans.iter().find(|span| span.covers(col, row)).copied()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::app::ui::{Layout, LayoutBuffer};
+    use crate::app::ui::Layout;
 
     use super::*;
 
+    const AREA: Area = Area {
+        x: 0,
+        y: 0,
+        cols: 100,
+        rows: 25,
+    };
+
     #[test]
     fn regular_table() {
-        let mut table = Table::with_capacity(5, 10);
+        let mut table = Table::with_capacity(3, 2);
         table
-            .add_row(Layout::Static(5), &|layout_buffer: &mut LayoutBuffer| {
-                layout_buffer.add_layout(Layout::Weighted(1));
-            })
-            .add_row(Layout::Weighted(1), &|layout_buffer: &mut LayoutBuffer| {
-                layout_buffer
-                    .add_layout(Layout::Static(5))
-                    .add_layout(Layout::Weighted(1))
-                    .add_layout(Layout::Percentage(0.1));
-            });
-
-        let area = Area {
-            x: 0,
-            y: 0,
-            cols: 100,
-            rows: 25,
-        };
-        table.calculate_areas(&area);
+            .add_column(Layout::Static(5))
+            .add_column(Layout::Weighted(1))
+            .add_column(Layout::Percentage(0.1))
+            .add_row(Layout::Static(5))
+            .add_row(Layout::Weighted(1))
+            .add_span(0, 0, 3, 1);
+        table.calculate_areas(&AREA);
 
         assert_eq!(
-            table.area(0, 0),
+            table.area(0, 0).unwrap(),
             Area {
                 x: 0,
                 y: 0,
@@ -99,7 +177,7 @@ mod tests {
             }
         );
         assert_eq!(
-            table.area(0, 1),
+            table.area(0, 1).unwrap(),
             Area {
                 x: 0,
                 y: 5,
@@ -108,7 +186,7 @@ mod tests {
             }
         );
         assert_eq!(
-            table.area(1, 1),
+            table.area(1, 1).unwrap(),
             Area {
                 x: 5,
                 y: 5,
@@ -117,7 +195,7 @@ mod tests {
             }
         );
         assert_eq!(
-            table.area(2, 1),
+            table.area(2, 1).unwrap(),
             Area {
                 x: 90,
                 y: 5,
@@ -126,4 +204,133 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn columns_line_up_between_rows() {
+        let mut table = Table::with_capacity(3, 4);
+        table
+            .add_column(Layout::Fill.with_min(40))
+            .add_column(Layout::Fill)
+            .add_column(Layout::Max(20));
+        for _ in 0..4 {
+            table.add_row(Layout::Fill);
+        }
+        table.calculate_areas(&AREA);
+
+        for col in 0..3 {
+            let column = table.area(col, 0).unwrap();
+            for row in 1..4 {
+                let cell = table.area(col, row).unwrap();
+                assert_eq!((cell.x, cell.cols), (column.x, column.cols));
+            }
+        }
+        let widths: Vec<u16> = (0..3).map(|col| table.area(col, 0).unwrap().cols).collect();
+        assert_eq!(widths, vec![40, 40, 20]);
+        assert!((0..4).all(|row| table.area(0, row).unwrap().rows == 6 || table.area(0, row).unwrap().rows == 7));
+    }
+
+    #[test]
+    fn spans() {
+        let mut table = Table::with_capacity(4, 4);
+        for _ in 0..4 {
+            table.add_column(Layout::Static(10)).add_row(Layout::Static(5));
+        }
+        table
+            .add_span(1, 1, 2, 3)
+            // Cut at the last column and row
+            .add_span(3, 3, 5, 5);
+        table.calculate_areas(&AREA);
+
+        let span = Area {
+            x: 10,
+            y: 5,
+            cols: 20,
+            rows: 15,
+        };
+        assert_eq!(table.area(1, 1).unwrap(), span);
+        assert_eq!(table.area(2, 3).unwrap(), span);
+        assert_eq!(
+            table.area(0, 2).unwrap(),
+            Area {
+                x: 0,
+                y: 10,
+                cols: 10,
+                rows: 5
+            }
+        );
+        assert_eq!(
+            table.area(3, 3).unwrap(),
+            Area {
+                x: 30,
+                y: 15,
+                cols: 10,
+                rows: 5
+            }
+        );
+    }
+
+    #[test]
+    fn cell_at() {
+        let mut table = Table::with_capacity(3, 2);
+        table
+            .add_column(Layout::Static(4))
+            .add_column(Layout::Static(4))
+            .add_column(Layout::Static(4))
+            .add_row(Layout::Static(2))
+            .add_row(Layout::Static(2))
+            .add_span(0, 0, 3, 1);
+
+        let area = Area {
+            x: 10,
+            y: 10,
+            cols: 20,
+            rows: 6,
+        };
+        table.calculate_areas(&area);
+
+        assert_eq!(table.cell_at(10, 10), Some((0, 0)));
+        assert_eq!(table.cell_at(21, 11), Some((0, 0)));
+        assert_eq!(table.cell_at(10, 12), Some((0, 1)));
+        assert_eq!(table.cell_at(14, 13), Some((1, 1)));
+        assert_eq!(table.cell_at(21, 12), Some((2, 1)));
+
+        // Outside of the table, below the last row and past the last column
+        assert_eq!(table.cell_at(9, 10), None);
+        assert_eq!(table.cell_at(10, 14), None);
+        assert_eq!(table.cell_at(22, 12), None);
+    }
+
+    #[test]
+    fn cells_outside_of_the_table_have_no_area() {
+        let mut table = Table::with_capacity(0, 0);
+        table.calculate_areas(&AREA);
+        assert_eq!(table.area(0, 0), None);
+
+        table.add_column(Layout::Fill).add_row(Layout::Fill).add_span(0, 0, 2, 2);
+        table.calculate_areas(&AREA);
+        assert_eq!(table.area(0, 0), Some(AREA));
+        assert_eq!(table.area(2, 0), None);
+        assert_eq!(table.area(0, 2), None);
+    }
+
+    #[test]
+    fn empty_spans_are_ignored() {
+        let mut table = Table::with_capacity(4, 4);
+        for _ in 0..4 {
+            table.add_column(Layout::Static(10)).add_row(Layout::Static(5));
+        }
+        // Inside the first span, but covering no cell
+        table.add_span(1, 1, 2, 3).add_span(2, 2, 0, 0).add_span(0, 0, 4, 0);
+        table.calculate_areas(&AREA);
+
+        assert_eq!(table.spans.len(), 1);
+        assert_eq!(table.area(0, 0).unwrap().cols, 10);
+    }
+
+    #[test]
+    #[should_panic(expected = "span at (0, 2) overlaps a span added before")]
+    fn spans_do_not_overlap() {
+        let mut table = Table::with_capacity(4, 4);
+        table.add_span(1, 1, 2, 3).add_span(0, 2, 2, 1);
+    }
 }
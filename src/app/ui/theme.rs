@@ -0,0 +1,178 @@
+use super::RGBA;
+
+/**
+ * What a color is used for. Widgets ask for roles and the theme in use decides the actual colors.
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Role {
+    /**
+     * Behind everything else in the frame
+     */
+    Background,
+    /**
+     * Inside of bordered surfaces
+     */
+    Surface,
+    Border,
+    Text,
+    /**
+     * Highlights such as titles
+     */
+    Accent,
+    Error,
+    Warning,
+    Selection,
+}
+
+/**
+ * Concrete color for every role
+ */
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Theme {
+    pub background: RGBA,
+    pub surface: RGBA,
+    pub border: RGBA,
+    pub text: RGBA,
+    pub accent: RGBA,
+    pub error: RGBA,
+    pub warning: RGBA,
+    pub selection: RGBA,
+}
+
+/**
+ * Built-in themes, see `Theme::by_name`
+ */
+pub const NAMES: [&str; 3] = ["dark", "light", "high-contrast"];
+
+pub const DARK: Theme = Theme {
+    background: RGBA::rgb(24, 24, 27),
+    surface: RGBA::rgb(39, 39, 44),
+    border: RGBA::rgb(99, 102, 112),
+    text: RGBA::rgb(220, 220, 224),
+    accent: RGBA::rgb(97, 175, 239),
+    error: RGBA::rgb(224, 108, 117),
+    warning: RGBA::rgb(229, 192, 123),
+    selection: RGBA::rgb(52, 74, 112),
+};
+
+pub const LIGHT: Theme = Theme {
+    background: RGBA::rgb(250, 250, 250),
+    surface: RGBA::rgb(238, 238, 240),
+    border: RGBA::rgb(160, 161, 167),
+    text: RGBA::rgb(40, 42, 46),
+    accent: RGBA::rgb(1, 103, 204),
+    error: RGBA::rgb(202, 18, 67),
+    warning: RGBA::rgb(152, 104, 1),
+    selection: RGBA::rgb(196, 218, 250),
+};
+
+/**
+ * Pure colors only, for low vision and poor displays
+ */
+pub const HIGH_CONTRAST: Theme = Theme {
+    background: RGBA::rgb(0, 0, 0),
+    surface: RGBA::rgb(0, 0, 0),
+    border: RGBA::rgb(255, 255, 255),
+    text: RGBA::rgb(255, 255, 255),
+    accent: RGBA::rgb(0, 255, 255),
+    error: RGBA::rgb(255, 0, 0),
+    warning: RGBA::rgb(255, 255, 0),
+    selection: RGBA::rgb(0, 0, 255),
+};
+
+impl Theme {
+    /**
+     * The theme used when none is configured
+     */
+    pub const fn dark() -> Self {
+        DARK
+    }
+
+    pub fn by_name(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(DARK),
+            "light" => Some(LIGHT),
+            "high-contrast" => Some(HIGH_CONTRAST),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, role: Role) -> RGBA {
+        match role {
+            Role::Background => self.background,
+            Role::Surface => self.surface,
+            Role::Border => self.border,
+            Role::Text => self.text,
+            Role::Accent => self.accent,
+            Role::Error => self.error,
+            Role::Warning => self.warning,
+            Role::Selection => self.selection,
+        }
+    }
+
+    pub fn set(&mut self, role: Role, color: RGBA) {
+        match role {
+            Role::Background => self.background = color,
+            Role::Surface => self.surface = color,
+            Role::Border => self.border = color,
+            Role::Text => self.text = color,
+            Role::Accent => self.accent = color,
+            Role::Error => self.error = color,
+            Role::Warning => self.warning = color,
+            Role::Selection => self.selection = color,
+        }
+    }
+}
+
+impl Role {
+    pub const ALL: [Role; 8] = [
+        Role::Background,
+        Role::Surface,
+        Role::Border,
+        Role::Text,
+        Role::Accent,
+        Role::Error,
+        Role::Warning,
+        Role::Selection,
+    ];
+
+    /**
+     * Name of the role in the config file
+     */
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Background => "background",
+            Role::Surface => "surface",
+            Role::Border => "border",
+            Role::Text => "text",
+            Role::Accent => "accent",
+            Role::Error => "error",
+            Role::Warning => "warning",
+            Role::Selection => "selection",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_name_is_a_theme() {
+        for name in NAMES {
+            assert!(Theme::by_name(name).is_some(), "{name}");
+        }
+        assert_eq!(Theme::by_name("solarized"), None);
+    }
+
+    #[test]
+    fn roles_are_set_and_read_back() {
+        let mut theme = Theme::dark();
+        for (index, role) in Role::ALL.into_iter().enumerate() {
+            theme.set(role, RGBA::rgb(index as u8, 0, 0));
+        }
+        for (index, role) in Role::ALL.into_iter().enumerate() {
+            assert_eq!(theme.get(role), RGBA::rgb(index as u8, 0, 0));
+        }
+    }
+}
@@ -1,16 +1,203 @@
+use std::{any::Any, error, fmt, io};
+
+mod cell;
 mod home;
+mod inspect;
 
+pub use cell::{CellChosen, CellView};
 pub use home::HomeView;
+pub use inspect::{parse_env, InspectView};
 pub use crate::app::renderer::Renderer;
 
-use super::event::Event;
+use super::{config::Config, event::Event};
+
+/**
+ * Views the app can be started on, see `by_name`
+ */
+pub const NAMES: [&str; 2] = ["home", "cell"];
+
+pub fn by_name(name: &str) -> Option<Box<dyn View>> {
+    match name {
+        "home" => Some(Box::new(HomeView::new())),
+        "cell" => Some(Box::new(HomeView::first_cell())),
+        _ => None,
+    }
+}
 
 pub trait View {
     fn init(&mut self) -> Result<(), Error>;
-    fn update(&mut self, event: &Event) -> Result<(), Error>;
+    /**
+     * Only the view on top of the navigation stack receives events. What the app does next is
+     * decided by the returned action, e.g. ESC can close a dialog in one view and quit in another.
+     */
+    fn update(&mut self, event: &Event) -> Result<Action, Error>;
+    /**
+     * Receives the custom messages sent by the view right above this one in the navigation stack
+     */
+    fn on_message(&mut self, _message: Message) -> Result<Action, Error> {
+        Ok(Action::None)
+    }
+    /**
+     * Every view in the navigation stack is told when the config file was changed and reloaded
+     * while the app is running, bottom first
+     */
+    fn on_config_change(&mut self, _config: &Config) -> Result<Action, Error> {
+        Ok(Action::None)
+    }
     fn render(&mut self, renderer: &mut dyn Renderer) -> Result<(), Error>;
+    /**
+     * Views that change on their own (timers, background work) return true to get a new frame
+     * drawn without waiting for input
+     */
+    fn needs_redraw(&self) -> bool {
+        false
+    }
     fn close(&mut self) -> Result<(), Error>;
 }
 
+pub enum Action {
+    None,
+    /**
+     * The view's state changed and a new frame should be drawn
+     */
+    Redraw,
+    Quit,
+    Navigate(Navigation),
+    /**
+     * Hand a message to the view below this one, e.g. a dialog reporting what the user picked
+     */
+    Custom(Message),
+}
+
+pub type Message = Box<dyn Any>;
+
+/**
+ * Changes to the app's view stack. The view on top of the stack is the one on screen and receives
+ * the events.
+ */
+pub enum Navigation {
+    /**
+     * Show a new view on top of the current one, which is kept to come back to
+     */
+    Push(Box<dyn View>),
+    /**
+     * Close the current view and go back to the one below it. Popping the root view quits.
+     */
+    Pop,
+    /**
+     * Close the current view and show a new one in its place
+     */
+    Replace(Box<dyn View>),
+    /**
+     * Close every view above the root one
+     */
+    PopToRoot,
+}
+
+#[derive(Debug)]
 pub enum Error {
+    Io(io::Error),
+    /**
+     * The view got into a state it cannot recover from
+     */
+    InvalidState(String),
+    /**
+     * Something the user should know about, the view keeps working
+     */
+    Message {
+        message: String,
+        source: Option<Box<dyn error::Error>>,
+    },
+}
+
+impl Error {
+    pub fn message<T: Into<String>>(message: T) -> Self {
+        Self::Message {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub fn invalid_state<T: Into<String>>(description: T) -> Self {
+        Self::InvalidState(description.into())
+    }
+
+    /**
+     * Fatal errors stop the app, the rest are shown to the user in the status bar
+     */
+    pub fn is_fatal(&self) -> bool {
+        match self {
+            Self::Io(_) | Self::InvalidState(_) => true,
+            Self::Message { .. } => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(_) => f.write_str("I/O failed"),
+            Self::InvalidState(description) => write!(f, "invalid state: {description}"),
+            Self::Message { message, .. } => f.write_str(message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::InvalidState(_) => None,
+            Self::Message { source, .. } => source.as_deref(),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn display() {
+        assert_eq!(Error::message("Nothing to show").to_string(), "Nothing to show");
+        assert_eq!(
+            Error::invalid_state("no table rows").to_string(),
+            "invalid state: no table rows"
+        );
+        assert_eq!(Error::from(io::Error::other("disk full")).to_string(), "I/O failed");
+    }
+
+    #[test]
+    fn source_chain() {
+        let err = Error::Message {
+            message: String::from("Could not load the file"),
+            source: Some(Box::new(Error::from(io::Error::other("permission denied")))),
+        };
+
+        let source = err.source().unwrap();
+        assert_eq!(source.to_string(), "I/O failed");
+        assert_eq!(source.source().unwrap().to_string(), "permission denied");
+        assert!(source.source().unwrap().source().is_none());
+    }
+
+    #[test]
+    fn fatality() {
+        assert!(!Error::message("Nothing to show").is_fatal());
+        assert!(Error::invalid_state("no table rows").is_fatal());
+        assert!(Error::from(io::Error::other("disk full")).is_fatal());
+    }
+
+    #[test]
+    fn every_name_opens_a_view() {
+        assert!(NAMES.iter().all(|name| by_name(name).is_some()));
+        assert!(by_name("nowhere").is_none());
+    }
 }
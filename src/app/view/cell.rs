@@ -0,0 +1,111 @@
+use crate::app::{
+    event::{Event, Key},
+    renderer::{Area, Renderer},
+    ui::{Align, Direction, Justify, Label, Layout, LayoutBuffer, Padding, Style, Surface},
+    View,
+};
+
+use super::{Action, Navigation};
+
+/**
+ * Size of the card: one line of text inside its border and padding
+ */
+const CARD_ROWS: u16 = 3;
+const CARD_COLS: u16 = 28;
+
+/**
+ * Sent to the view below when the user picks the cell on display
+ */
+pub struct CellChosen {
+    pub col: usize,
+    pub row: usize,
+}
+
+/**
+ * Detail page for one cell of the home table. The arrows move to the neighbouring cells of the
+ * same row without growing the navigation stack.
+ */
+pub struct CellView {
+    col: usize,
+    row: usize,
+    col_count: usize,
+    layout_buffer: LayoutBuffer,
+}
+
+impl CellView {
+    pub fn from(col: usize, row: usize, col_count: usize) -> Self {
+        Self {
+            col,
+            row,
+            col_count,
+            // The card sits centered at the top, the help at the bottom
+            layout_buffer: LayoutBuffer::with_capacity(2)
+                .with_margin(Padding::high_and_wide(0, 1))
+                .with_justify(Justify::SpaceBetween)
+                .with_align(Align::Center),
+        }
+    }
+}
+
+impl View for CellView {
+    fn init(&mut self) -> Result<(), super::Error> {
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<Action, super::Error> {
+        let Event::Key(key) = event else {
+            return Ok(Action::None);
+        };
+        let action = match key.get_key() {
+            Key::Esc | Key::Backspace => Action::Navigate(Navigation::Pop),
+            Key::Home => Action::Navigate(Navigation::PopToRoot),
+            Key::Enter => Action::Custom(Box::new(CellChosen {
+                col: self.col,
+                row: self.row,
+            })),
+            Key::Left if self.col > 0 => Action::Navigate(Navigation::Replace(Box::new(
+                CellView::from(self.col - 1, self.row, self.col_count),
+            ))),
+            Key::Right if self.col + 1 < self.col_count => Action::Navigate(Navigation::Replace(
+                Box::new(CellView::from(self.col + 1, self.row, self.col_count)),
+            )),
+            _ => Action::None,
+        };
+        Ok(action)
+    }
+
+    fn render(&mut self, renderer: &mut dyn Renderer) -> Result<(), super::Error> {
+        let frame_size = renderer.frame_size();
+
+        self.layout_buffer.clear();
+        self.layout_buffer
+            .add_layout(Layout::Static(CARD_ROWS).with_cross_size(CARD_COLS))
+            .add_layout(Layout::Static(1))
+            .calculate_areas(Direction::Vertical, &frame_size);
+
+        let (col, row) = (self.col, self.row);
+        let mut surface = Surface::from(
+            Padding::high_and_wide(1, 2),
+            |internal_renderer: &mut dyn Renderer, content_area: &Area| {
+                internal_renderer.render(
+                    &mut Label::from(format!("This is cell {col}, {row}")),
+                    *content_area,
+                    &Style::new(),
+                );
+            },
+        );
+        renderer.render(&mut surface, *self.layout_buffer.area(0).unwrap(), &Style::bordered());
+
+        renderer.render(
+            &mut Label::from("Left/Right: neighbouring cells, Enter: select, Esc: back, Home: home"),
+            *self.layout_buffer.area(1).unwrap(),
+            &Style::new(),
+        );
+
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), super::Error> {
+        Ok(())
+    }
+}
@@ -1,32 +1,102 @@
 use crate::app::{
-    event::{Event, Key},
+    event::{Event, Key, MouseButton, MouseKind},
     renderer::{Area, Renderer},
-    ui::{Borders, Direction, Label, Layout, LayoutBuffer, Padding, Style, Surface, Table, RGBA},
+    ui::{Borders, Color, Direction, Label, Layout, LayoutNode, LayoutTree, Padding, Role, Style, Surface, Table},
     View,
 };
 
+use super::{Action, CellChosen, CellView, Message, Navigation};
+
 const MAIN_TABLE_ROW_COUNT: u16 = 5;
 const MAIN_TABLE_COL_COUNT: u16 = 3;
+/**
+ * Colors a selected cell can be highlighted with, picked by clicking the swatches
+ */
+const SWATCHES: [Role; 4] = [Role::Error, Role::Warning, Role::Accent, Role::Selection];
 
 pub struct HomeView {
+    headline: String,
+    focused: bool,
     should_draw_second: bool,
-    layout_buffer: LayoutBuffer,
-    horizontal_layout_buffer: LayoutBuffer,
+    selected_cell: Option<(usize, usize)>,
+    selected_swatch: usize,
+    layout: LayoutTree,
     main_table: Table,
 }
 
 impl HomeView {
     pub fn new() -> Self {
         Self {
+            headline: String::from("This is some text"),
+            focused: true,
             should_draw_second: false,
-            layout_buffer: LayoutBuffer::with_capacity(20),
-            horizontal_layout_buffer: LayoutBuffer::with_capacity(5),
+            selected_cell: None,
+            selected_swatch: 3,
+            layout: LayoutTree::from(Self::layout()),
             main_table: Table::with_capacity(
                 MAIN_TABLE_COL_COUNT as usize,
                 MAIN_TABLE_ROW_COUNT as usize,
             ),
         }
     }
+
+    /**
+     * The screen from top to bottom. The second text starts out hidden, the surface keeps room
+     * for its border and padding and the table for all of its rows.
+     */
+    fn layout() -> LayoutNode {
+        let mut swatches = LayoutNode::from("swatches", Layout::Static(3))
+            .with_direction(Direction::Horizontal)
+            .with_gap(1);
+        for role in SWATCHES {
+            swatches = swatches.with_child(LayoutNode::from(role.name(), Layout::Fill));
+        }
+
+        LayoutNode::from("screen", Layout::Fill)
+            .with_child(LayoutNode::from("headline", Layout::Static(1)))
+            .with_child(swatches)
+            .with_child(LayoutNode::from("second", Layout::Percentage(0.05)))
+            .with_child(LayoutNode::from("surface", Layout::Weighted(1).with_min(3)))
+            .with_child(LayoutNode::from("table", Layout::Min(MAIN_TABLE_ROW_COUNT * 3)))
+    }
+
+    /**
+     * Detail view of the first cell of the table
+     */
+    pub fn first_cell() -> CellView {
+        CellView::from(0, 1, MAIN_TABLE_COL_COUNT as usize)
+    }
+
+    /**
+     * Clicking a cell selects it, clicking the selected cell again opens it
+     */
+    fn on_click(&mut self, col: u16, row: u16) -> Action {
+        if let Some(swatch) = SWATCHES.iter().position(|role| {
+            self.layout
+                .area(role.name())
+                .is_some_and(|area| area.contains(col, row))
+        }) {
+            self.selected_swatch = swatch;
+            return Action::Redraw;
+        }
+
+        // The first row is the table title, only the rows below it hold cells
+        let clicked_cell = self
+            .main_table
+            .cell_at(col, row)
+            .filter(|(_, cell_row)| *cell_row > 0);
+        if let Some((cell_col, cell_row)) = clicked_cell {
+            if self.selected_cell == clicked_cell {
+                return Action::Navigate(Navigation::Push(Box::new(CellView::from(
+                    cell_col,
+                    cell_row,
+                    MAIN_TABLE_COL_COUNT as usize,
+                ))));
+            }
+        }
+        self.selected_cell = clicked_cell;
+        Action::Redraw
+    }
 }
 
 impl View for HomeView {
@@ -34,69 +104,81 @@ impl View for HomeView {
         Ok(())
     }
 
-    fn update(&mut self, event: &Event) -> Result<(), super::Error> {
-        match event.get_key() {
-            Key::ENTER => {
-                self.should_draw_second = !self.should_draw_second;
+    fn update(&mut self, event: &Event) -> Result<Action, super::Error> {
+        let action = match event {
+            Event::Key(key) => match key.get_key() {
+                Key::Esc => Action::Quit,
+                Key::Enter => {
+                    self.should_draw_second = !self.should_draw_second;
+                    Action::Redraw
+                }
+                _ => Action::None,
+            },
+            Event::Mouse(mouse) => match mouse.get_kind() {
+                MouseKind::Press(MouseButton::Left) => self.on_click(mouse.get_col(), mouse.get_row()),
+                _ => Action::None,
+            },
+            Event::Paste(text) => {
+                if text.trim().is_empty() {
+                    return Err(super::Error::message("Nothing to paste, the clipboard is empty"));
+                }
+                // The headline is a single row, so keep pasted text on one line
+                self.headline = text.lines().collect::<Vec<&str>>().join(" ");
+                Action::Redraw
+            }
+            Event::FocusGained => {
+                self.focused = true;
+                Action::Redraw
+            }
+            Event::FocusLost => {
+                self.focused = false;
+                Action::Redraw
             }
-            _ => {}
+            // Layouts are recalculated from the frame size on every render
+            Event::Resize { .. } => Action::None,
+        };
+        Ok(action)
+    }
+
+    fn on_message(&mut self, message: Message) -> Result<Action, super::Error> {
+        match message.downcast::<CellChosen>() {
+            Ok(chosen) => {
+                self.selected_cell = Some((chosen.col, chosen.row));
+                Ok(Action::Navigate(Navigation::Pop))
+            }
+            Err(_) => Err(super::Error::invalid_state("home view received an unknown message")),
         }
-        Ok(())
     }
 
     fn render(&mut self, renderer: &mut dyn Renderer) -> Result<(), super::Error> {
         let frame_size = renderer.frame_size();
 
-        self.layout_buffer.clear();
-        self.layout_buffer
-            .add_layout(Layout::Static(1))
-            .add_layout(Layout::Static(3));
-        if self.should_draw_second {
-            self.layout_buffer.add_layout(Layout::Percentage(0.05));
-        }
-        self.layout_buffer
-            .add_layout(Layout::Weighted(1))
-            .add_layout(Layout::Weighted(1))
-            .calculate_areas(Direction::Vertical, &frame_size);
+        self.layout.set_hidden("second", !self.should_draw_second);
+        self.layout.calculate_areas(&frame_size);
 
+        let headline_style = if self.focused {
+            Style::new()
+        } else {
+            // Dimmed while the terminal is in the background
+            Style::from(Borders::none(), Color::Inherit, Role::Border)
+        };
         renderer.render(
-            &mut Label::from("This is some text"),
-            *self.layout_buffer.area(0).unwrap(),
-            &Style::new(),
+            &mut Label::from(self.headline.as_str()),
+            *self.layout.area("headline").unwrap(),
+            &headline_style,
         );
 
-        self.horizontal_layout_buffer.clear();
-        self.horizontal_layout_buffer
-            .add_layout(Layout::Weighted(1))
-            .add_layout(Layout::Weighted(1))
-            .add_layout(Layout::Weighted(1))
-            .add_layout(Layout::Weighted(1))
-            .calculate_areas(Direction::Horizontal, &self.layout_buffer.area(1).unwrap());
-
-        renderer.render(
-            &mut Surface::from(Padding::none(), &|_: &mut dyn Renderer, _: &Area| {}), 
-            *self.horizontal_layout_buffer.area(0).unwrap(), 
-            &Style::from(Borders::none(), RGBA::red(), RGBA::transparent()));
-
-        renderer.render(
-            &mut Surface::from(Padding::none(), &|_: &mut dyn Renderer, _: &Area| {}), 
-            *self.horizontal_layout_buffer.area(1).unwrap(), 
-            &Style::from(Borders::none(), RGBA::green(), RGBA::transparent()));
-
-        renderer.render(
-            &mut Surface::from(Padding::none(), &|_: &mut dyn Renderer, _: &Area| {}), 
-            *self.horizontal_layout_buffer.area(2).unwrap(), 
-            &Style::from(Borders::none(), RGBA::blue(), RGBA::transparent()));
-
-        renderer.render(
-            &mut Surface::from(Padding::none(), &|_: &mut dyn Renderer, _: &Area| {}), 
-            *self.horizontal_layout_buffer.area(3).unwrap(), 
-            &Style::from(Borders::none(), RGBA::black(), RGBA::transparent()));
+        for role in SWATCHES {
+            renderer.render(
+                &mut Surface::from(Padding::none(), &|_: &mut dyn Renderer, _: &Area| {}),
+                *self.layout.area(role.name()).unwrap(),
+                &Style::from(Borders::none(), role, Color::Inherit));
+        }
 
         if self.should_draw_second {
             renderer.render(
                 &mut Label::from("This is the second text!"),
-                *self.layout_buffer.area(2).unwrap(),
+                *self.layout.area("second").unwrap(),
                 &Style::new(),
             );
         }
@@ -111,35 +193,18 @@ impl View for HomeView {
                 );
             },
         );
-        renderer.render(
-            &mut surface,
-            *self
-                .layout_buffer
-                .area(if self.should_draw_second { 3 } else { 2 })
-                .unwrap(),
-            &Style::bordered(),
-        );
+        renderer.render(&mut surface, *self.layout.area("surface").unwrap(), &Style::bordered());
 
         self.main_table.clear();
-        self.main_table
-            .add_row(Layout::Static(3), &|layout_buffer: &mut LayoutBuffer| {
-                layout_buffer.add_layout(Layout::Weighted(1));
-            });
-
-        for _ in 0..MAIN_TABLE_ROW_COUNT - 1 {
-            self.main_table
-                .add_row(Layout::Static(3), &|layout_buffer: &mut LayoutBuffer| {
-                    for _ in 0..MAIN_TABLE_COL_COUNT {
-                        layout_buffer.add_layout(Layout::Weighted(1));
-                    }
-                });
+        for _ in 0..MAIN_TABLE_COL_COUNT {
+            self.main_table.add_column(Layout::Ratio(1, MAIN_TABLE_COL_COUNT));
         }
-
-        let table_area = self
-            .layout_buffer
-            .area(if self.should_draw_second { 4 } else { 3 })
-            .unwrap();
-        self.main_table.calculate_areas(table_area);
+        for _ in 0..MAIN_TABLE_ROW_COUNT {
+            self.main_table.add_row(Layout::Static(3));
+        }
+        // The title takes the whole first row
+        self.main_table.add_span(0, 0, MAIN_TABLE_COL_COUNT as usize, 1);
+        self.main_table.calculate_areas(self.layout.area("table").unwrap());
 
         let mut table_title_surface = Surface::from(
             Padding::high_and_wide(1, 2),
@@ -147,13 +212,13 @@ impl View for HomeView {
                 internal_renderer.render(
                     &mut Label::from("This is a table title"),
                     *content_area,
-                    &Style::from(Borders::none(), RGBA::transparent(), RGBA::red()),
+                    &Style::from(Borders::none(), Color::Inherit, Role::Accent),
                 );
             },
         );
         renderer.render(
             &mut table_title_surface,
-            self.main_table.row_area(0).clone(),
+            self.main_table.area(0, 0).unwrap(),
             &Style::bordered(),
         );
 
@@ -168,10 +233,14 @@ impl View for HomeView {
                 };
 
                 let mut table_cell = Surface::from(Padding::around(1), &on_cell_draw);
+                let mut cell_style = Style::bordered();
+                if self.selected_cell == Some((col, row)) {
+                    cell_style.background = SWATCHES[self.selected_swatch].into();
+                }
                 renderer.render(
                     &mut table_cell,
-                    self.main_table.area(col, row),
-                    &Style::bordered(),
+                    self.main_table.area(col, row).unwrap(),
+                    &cell_style,
                 );
             }
         }
@@ -183,3 +252,95 @@ impl View for HomeView {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{
+        buffer::CellBuffer,
+        event::{KeyEvent, MouseEvent},
+        headless::HeadlessScreen,
+        snapshot::{assert_snapshot, assert_styled_snapshot, render_view},
+        ui::Theme,
+    };
+
+    const SNAPSHOT_AREA: Area = Area {
+        x: 0,
+        y: 0,
+        cols: 60,
+        rows: 40,
+    };
+
+    fn run(events: Vec<Event>) -> CellBuffer {
+        HeadlessScreen::with_size(60, 40)
+            .with_events(events)
+            .run(Box::new(HomeView::new()))
+            .unwrap()
+            .buffer()
+    }
+
+    fn contains(buffer: &CellBuffer, text: &str) -> bool {
+        buffer.to_text().lines().any(|line| line.contains(text))
+    }
+
+    #[test]
+    fn first_frame() {
+        let buffer = run(Vec::new());
+
+        assert!(buffer.row_text(0).starts_with("This is some text"));
+        assert!(contains(&buffer, "This should be far off"));
+        assert!(contains(&buffer, "This is a table title"));
+        assert!(!contains(&buffer, "This is the second text!"));
+    }
+
+    #[test]
+    fn enter_toggles_the_second_text() {
+        let buffer = run(vec![Event::Key(KeyEvent::from(Key::Enter))]);
+        assert!(contains(&buffer, "This is the second text!"));
+
+        let enter = || Event::Key(KeyEvent::from(Key::Enter));
+        let buffer = run(vec![enter(), enter()]);
+        assert!(!contains(&buffer, "This is the second text!"));
+    }
+
+    #[test]
+    fn paste_replaces_the_headline() {
+        let buffer = run(vec![Event::Paste(String::from("pasted\ntext"))]);
+
+        assert!(buffer.row_text(0).starts_with("pasted text"));
+    }
+
+    #[test]
+    fn clicking_a_cell_selects_it() {
+        let buffer = run(Vec::new());
+        let (x, y) = buffer
+            .to_text()
+            .lines()
+            .enumerate()
+            .find_map(|(y, line)| {
+                line.find("This is cell 1, 2")
+                    .map(|x| (line[..x].chars().count() as u16, y as u16))
+            })
+            .unwrap();
+        assert_eq!(buffer.get(x, y).unwrap().background, Theme::dark().surface);
+
+        let click = MouseEvent::from(MouseKind::Press(MouseButton::Left), x, y);
+        let buffer = run(vec![Event::Mouse(click)]);
+        assert_eq!(buffer.get(x, y).unwrap().background, Theme::dark().selection);
+    }
+
+    #[test]
+    fn snapshot_default() {
+        let mut view = HomeView::new();
+
+        assert_styled_snapshot("home_default", &render_view(&mut view, SNAPSHOT_AREA));
+    }
+
+    #[test]
+    fn snapshot_second_text() {
+        let mut view = HomeView::new();
+        view.update(&Event::Key(KeyEvent::from(Key::Enter))).unwrap();
+
+        assert_snapshot("home_second_text", &render_view(&mut view, SNAPSHOT_AREA));
+    }
+}
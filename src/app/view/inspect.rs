@@ -0,0 +1,168 @@
+use crate::app::{
+    event::{Event, Key},
+    renderer::{Area, Renderer},
+    ui::{Borders, Color, Direction, Label, Layout, LayoutBuffer, Role, Style},
+    View,
+};
+
+use super::Action;
+
+/**
+ * Lists the variables of an environment, one `NAME=value` per row. Up and Down scroll the list.
+ */
+pub struct InspectView {
+    title: String,
+    variables: Vec<(String, String)>,
+    /**
+     * Index of the first variable on screen
+     */
+    scroll: usize,
+    layout_buffer: LayoutBuffer,
+}
+
+impl InspectView {
+    pub fn from(title: impl Into<String>, variables: Vec<(String, String)>) -> Self {
+        Self {
+            title: title.into(),
+            variables,
+            scroll: 0,
+            layout_buffer: LayoutBuffer::with_capacity(2),
+        }
+    }
+}
+
+/**
+ * Variables of an environment file: `NAME=value` lines, optionally starting with `export`. Blank
+ * lines, comments and lines without `=` are skipped, quotes around a value are removed.
+ */
+pub fn parse_env(text: &str) -> Vec<(String, String)> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (name, value) = line.split_once('=')?;
+            let value = value.trim();
+            let unquoted = ['"', '\'']
+                .iter()
+                .find_map(|quote| value.strip_prefix(*quote)?.strip_suffix(*quote))
+                .unwrap_or(value);
+            Some((String::from(name.trim()), String::from(unquoted)))
+        })
+        .collect()
+}
+
+impl View for InspectView {
+    fn init(&mut self) -> Result<(), super::Error> {
+        Ok(())
+    }
+
+    fn update(&mut self, event: &Event) -> Result<Action, super::Error> {
+        let Event::Key(key) = event else {
+            return Ok(Action::None);
+        };
+        let action = match key.get_key() {
+            Key::Esc => Action::Quit,
+            Key::Up if self.scroll > 0 => {
+                self.scroll -= 1;
+                Action::Redraw
+            }
+            Key::Down if self.scroll + 1 < self.variables.len() => {
+                self.scroll += 1;
+                Action::Redraw
+            }
+            _ => Action::None,
+        };
+        Ok(action)
+    }
+
+    fn render(&mut self, renderer: &mut dyn Renderer) -> Result<(), super::Error> {
+        let frame_size = renderer.frame_size();
+
+        self.layout_buffer.clear();
+        self.layout_buffer
+            .add_layout(Layout::Static(1))
+            .add_layout(Layout::Fill)
+            .calculate_areas(Direction::Vertical, &frame_size);
+
+        let title = format!("{} ({} variables)", self.title, self.variables.len());
+        renderer.render(
+            &mut Label::from(title),
+            *self.layout_buffer.area(0).unwrap(),
+            &Style::from(Borders::none(), Color::Inherit, Role::Accent),
+        );
+
+        let list_area = *self.layout_buffer.area(1).unwrap();
+        let visible = self.variables.iter().skip(self.scroll).take(list_area.rows as usize);
+        for (row, (name, value)) in (list_area.y..).zip(visible) {
+            renderer.render(
+                &mut Label::from(format!("{name}={value}")),
+                Area {
+                    y: row,
+                    rows: 1,
+                    ..list_area
+                },
+                &Style::new(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), super::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{event::KeyEvent, snapshot::render_view};
+
+    const AREA: Area = Area {
+        x: 0,
+        y: 0,
+        cols: 40,
+        rows: 3,
+    };
+
+    fn variables(names: &[&str]) -> Vec<(String, String)> {
+        names
+            .iter()
+            .map(|name| (String::from(*name), String::from("1")))
+            .collect()
+    }
+
+    #[test]
+    fn env_files() {
+        let text = "# comment\n\nexport A=1\nB = \"two words\"\nC='x'\nnot a variable\nD=\n";
+
+        assert_eq!(
+            parse_env(text),
+            vec![
+                (String::from("A"), String::from("1")),
+                (String::from("B"), String::from("two words")),
+                (String::from("C"), String::from("x")),
+                (String::from("D"), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_and_scrolls_the_variables() {
+        let mut view = InspectView::from("environment file .env", variables(&["A", "B", "C"]));
+        let rows: Vec<String> = render_view(&mut view, AREA)
+            .to_text()
+            .lines()
+            .map(|row| String::from(row.trim_end()))
+            .collect();
+        assert_eq!(rows, vec!["environment file .env (3 variables)", "A=1", "B=1"]);
+
+        let down = Event::Key(KeyEvent::from(Key::Down));
+        assert!(matches!(view.update(&down).unwrap(), Action::Redraw));
+        assert!(matches!(view.update(&down).unwrap(), Action::Redraw));
+        // The last variable stays on screen
+        assert!(matches!(view.update(&down).unwrap(), Action::None));
+        assert_eq!(render_view(&mut view, AREA).row_text(1).trim_end(), "C=1");
+    }
+}
@@ -0,0 +1,490 @@
+use std::{error, fmt, path::PathBuf};
+
+use crate::app::{Config, Format, VIEW_NAMES};
+
+const DEFAULT_PRINT_SIZE: (u16, u16) = (80, 24);
+/**
+ * Options that take no value
+ */
+const FLAGS: [&str; 8] = ["-h", "--help", "-V", "--version", "--no-alt-screen", "--inline", "--print", "--"];
+
+/**
+ * What the command line asks for
+ */
+#[derive(PartialEq, Debug)]
+pub enum Command {
+    Help,
+    Version,
+    Run(Options),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Options {
+    /**
+     * One of `VIEW_NAMES`, overrides the config file
+     */
+    pub view: Option<String>,
+    pub config: Option<PathBuf>,
+    /**
+     * Overrides the config file
+     */
+    pub theme: Option<String>,
+    pub log: Option<PathBuf>,
+    pub alt_screen: bool,
+    /**
+     * Render one frame to stdout instead of running on the terminal
+     */
+    pub print: Option<Print>,
+    pub target: Option<Target>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct Print {
+    pub cols: u16,
+    pub rows: u16,
+    pub format: Format,
+}
+
+/**
+ * What to inspect
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub enum Target {
+    EnvFile(PathBuf),
+    /**
+     * Program and its arguments, everything after `--`
+     */
+    Command(Vec<String>),
+}
+
+impl Options {
+    /**
+     * Let the command line win over the config file
+     */
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(view) = &self.view {
+            config.view = view.clone();
+        }
+        if let Some(theme) = &self.theme {
+            config.theme = Some(theme.clone());
+        }
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EnvFile(path) => write!(f, "environment file {}", path.display()),
+            Self::Command(command) => write!(f, "command {}", command.join(" ")),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum Error {
+    UnknownOption(String),
+    MissingValue(&'static str),
+    /**
+     * A flag given a value with `=`, e.g. `--print=yes`
+     */
+    UnexpectedValue(String),
+    InvalidValue {
+        option: &'static str,
+        value: String,
+        expected: String,
+    },
+    /**
+     * The first option only makes sense together with the second
+     */
+    Requires(&'static str, &'static str),
+    Conflict(&'static str, &'static str),
+    NotAFile {
+        option: &'static str,
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOption(option) => write!(f, "unknown option {option}"),
+            Self::MissingValue(option) => write!(f, "{option} needs a value"),
+            Self::UnexpectedValue(option) => write!(f, "{option} does not take a value"),
+            Self::InvalidValue {
+                option,
+                value,
+                expected,
+            } => write!(f, "invalid value {value:?} for {option}, expected {expected}"),
+            Self::Requires(option, required) => write!(f, "{option} only applies to {required}"),
+            Self::Conflict(first, second) => write!(f, "{first} and {second} cannot be combined"),
+            Self::NotAFile { option, path } => {
+                write!(f, "{} given to {option} is not a file", path.display())
+            }
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+pub fn usage() -> &'static str {
+    "usage: visualnv [OPTIONS] [--env-file PATH | -- COMMAND [ARGS...]]"
+}
+
+pub fn help() -> String {
+    format!(
+        "visualnv {}
+
+{}
+
+Options:
+  -h, --help              Print this help and exit
+  -V, --version           Print the version and exit
+      --view NAME         View to start on: {} [default: home]
+      --config PATH       Read the configuration from PATH instead of
+                          $XDG_CONFIG_HOME/visualnv/config.toml
+      --theme NAME        Color theme: dark, light, high-contrast or one defined in
+                          the configuration [default: dark]
+      --log PATH          Append diagnostics to PATH
+      --no-alt-screen     Draw over the shell instead of on the alternate screen
+      --inline            Same as --no-alt-screen
+      --env-file PATH     List the variables of an environment file instead of
+                          starting on --view
+      --print             Render one frame to stdout and exit
+      --size COLSxROWS    Frame size for --print [default: {}x{}]
+      --format FORMAT     Output of --print: text, html or svg [default: text]
+  -- COMMAND [ARGS...]    List the current environment, which COMMAND would inherit,
+                          instead of starting on --view. COMMAND is not run.
+",
+        version(),
+        usage(),
+        VIEW_NAMES.join(", "),
+        DEFAULT_PRINT_SIZE.0,
+        DEFAULT_PRINT_SIZE.1,
+    )
+}
+
+pub fn version() -> &'static str {
+    env!("CARGO_PKG_VERSION")
+}
+
+/**
+ * Options take their value either as the next argument or after `=`, e.g. `--view=cell`. Flags
+ * given a value that way are an error.
+ * Checks that need the file system are left to `validate`.
+ */
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, Error> {
+    let mut view = None;
+    let mut config = None;
+    let mut theme = None;
+    let mut log = None;
+    let mut alt_screen = true;
+    let mut print = false;
+    let mut size = None;
+    let mut format = None;
+    let mut env_file = None;
+    let mut command = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(String::from(value))),
+            _ => (arg.as_str(), None),
+        };
+        if inline_value.is_some() && FLAGS.contains(&name) {
+            return Err(Error::UnexpectedValue(String::from(name)));
+        }
+        let mut value = |option: &'static str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or(Error::MissingValue(option))
+        };
+
+        match name {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--view" => view = Some(parse_view(value("--view")?)?),
+            "--config" => config = Some(PathBuf::from(value("--config")?)),
+            "--theme" => theme = Some(parse_theme(value("--theme")?)?),
+            "--log" => log = Some(PathBuf::from(value("--log")?)),
+            "--no-alt-screen" | "--inline" => alt_screen = false,
+            "--env-file" => env_file = Some(PathBuf::from(value("--env-file")?)),
+            "--print" => print = true,
+            "--size" => size = Some(parse_size(value("--size")?)?),
+            "--format" => format = Some(parse_format(value("--format")?)?),
+            "--" => {
+                command = Some(args.by_ref().collect::<Vec<String>>());
+                break;
+            }
+            _ => return Err(Error::UnknownOption(arg)),
+        }
+    }
+
+    if !print && size.is_some() {
+        return Err(Error::Requires("--size", "--print"));
+    }
+    if !print && format.is_some() {
+        return Err(Error::Requires("--format", "--print"));
+    }
+    let target = match (env_file, command) {
+        (Some(_), Some(_)) => return Err(Error::Conflict("--env-file", "a command")),
+        (_, Some(command)) if command.is_empty() => return Err(Error::MissingValue("--")),
+        (Some(path), None) => Some(Target::EnvFile(path)),
+        (None, Some(command)) => Some(Target::Command(command)),
+        (None, None) => None,
+    };
+    let print = print.then(|| {
+        let (cols, rows) = size.unwrap_or(DEFAULT_PRINT_SIZE);
+        Print {
+            cols,
+            rows,
+            format: format.unwrap_or(Format::Text),
+        }
+    });
+
+    Ok(Command::Run(Options {
+        view,
+        config,
+        theme,
+        log,
+        alt_screen,
+        print,
+        target,
+    }))
+}
+
+/**
+ * Files named on the command line have to exist before the terminal is taken over
+ */
+pub fn validate(options: &Options) -> Result<(), Error> {
+    let mut files = vec![("--config", &options.config)];
+    let env_file = match &options.target {
+        Some(Target::EnvFile(path)) => Some(path.clone()),
+        _ => None,
+    };
+    files.push(("--env-file", &env_file));
+
+    for (option, path) in files {
+        if let Some(path) = path {
+            if !path.is_file() {
+                return Err(Error::NotAFile {
+                    option,
+                    path: path.clone(),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_view(value: String) -> Result<String, Error> {
+    if !VIEW_NAMES.contains(&value.as_str()) {
+        return Err(Error::InvalidValue {
+            option: "--view",
+            value,
+            expected: format!("one of {}", VIEW_NAMES.join(", ")),
+        });
+    }
+    Ok(value)
+}
+
+fn parse_theme(value: String) -> Result<String, Error> {
+    let is_name = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    if value.is_empty() || !value.chars().all(is_name) {
+        return Err(Error::InvalidValue {
+            option: "--theme",
+            value,
+            expected: String::from("a name made of letters, digits, - and _"),
+        });
+    }
+    Ok(value)
+}
+
+fn parse_format(value: String) -> Result<Format, Error> {
+    match value.as_str() {
+        "text" => Ok(Format::Text),
+        "html" => Ok(Format::Html),
+        "svg" => Ok(Format::Svg),
+        _ => Err(Error::InvalidValue {
+            option: "--format",
+            value,
+            expected: String::from("text, html or svg"),
+        }),
+    }
+}
+
+/**
+ * `COLSxROWS`, both at least 1
+ */
+fn parse_size(value: String) -> Result<(u16, u16), Error> {
+    let size = value.split_once('x').and_then(|(cols, rows)| {
+        let cols: u16 = cols.parse().ok()?;
+        let rows: u16 = rows.parse().ok()?;
+        (cols > 0 && rows > 0).then_some((cols, rows))
+    });
+    size.ok_or(Error::InvalidValue {
+        option: "--size",
+        value,
+        expected: String::from("COLSxROWS like 120x40"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_strs(args: &[&str]) -> Result<Command, Error> {
+        parse(args.iter().map(|arg| String::from(*arg)))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse_strs(args) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("expected options, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        assert_eq!(
+            options(&[]),
+            Options {
+                view: None,
+                config: None,
+                theme: None,
+                log: None,
+                alt_screen: true,
+                print: None,
+                target: None,
+            }
+        );
+    }
+
+    #[test]
+    fn help_and_version_win() {
+        assert_eq!(parse_strs(&["--view", "cell", "-h"]), Ok(Command::Help));
+        assert_eq!(parse_strs(&["--version", "--bogus"]), Ok(Command::Version));
+        assert!(help().contains("--no-alt-screen"));
+    }
+
+    #[test]
+    fn values() {
+        let options = options(&[
+            "--view=cell",
+            "--config",
+            "visualnv.toml",
+            "--theme",
+            "high-contrast",
+            "--log=/tmp/visualnv.log",
+            "--inline",
+            "--env-file",
+            ".env",
+        ]);
+
+        assert_eq!(options.view.as_deref(), Some("cell"));
+        assert_eq!(options.config, Some(PathBuf::from("visualnv.toml")));
+        assert_eq!(options.theme.as_deref(), Some("high-contrast"));
+        assert_eq!(options.log, Some(PathBuf::from("/tmp/visualnv.log")));
+        assert!(!options.alt_screen);
+        assert_eq!(options.target, Some(Target::EnvFile(PathBuf::from(".env"))));
+    }
+
+    #[test]
+    fn print() {
+        assert_eq!(
+            options(&["--size", "120x40", "--format", "svg", "--print"]).print,
+            Some(Print {
+                cols: 120,
+                rows: 40,
+                format: Format::Svg
+            })
+        );
+        assert_eq!(
+            options(&["--print"]).print,
+            Some(Print {
+                cols: 80,
+                rows: 24,
+                format: Format::Text
+            })
+        );
+    }
+
+    #[test]
+    fn everything_after_the_separator_is_the_command() {
+        assert_eq!(
+            options(&["--view", "cell", "--", "env", "--view", "x"]).target,
+            Some(Target::Command(vec![
+                String::from("env"),
+                String::from("--view"),
+                String::from("x")
+            ]))
+        );
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        assert_eq!(parse_strs(&["--bogus"]), Err(Error::UnknownOption(String::from("--bogus"))));
+        assert_eq!(parse_strs(&["--config"]), Err(Error::MissingValue("--config")));
+        assert_eq!(parse_strs(&["--"]), Err(Error::MissingValue("--")));
+        assert_eq!(parse_strs(&["--print=x"]), Err(Error::UnexpectedValue(String::from("--print"))));
+        assert_eq!(parse_strs(&["--help="]), Err(Error::UnexpectedValue(String::from("--help"))));
+        assert_eq!(parse_strs(&["--size", "120x40"]), Err(Error::Requires("--size", "--print")));
+        assert_eq!(
+            parse_strs(&["--env-file", ".env", "--", "env"]),
+            Err(Error::Conflict("--env-file", "a command"))
+        );
+        assert!(matches!(
+            parse_strs(&["--view", "nowhere"]),
+            Err(Error::InvalidValue { option: "--view", .. })
+        ));
+        assert!(matches!(
+            parse_strs(&["--theme", "../dark"]),
+            Err(Error::InvalidValue { option: "--theme", .. })
+        ));
+        for size in ["120", "0x40", "120x-1", "x"] {
+            assert!(matches!(
+                parse_strs(&["--print", "--size", size]),
+                Err(Error::InvalidValue { option: "--size", .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let mut options = options(&["--config", "/nonexistent/visualnv.toml"]);
+        assert_eq!(
+            validate(&options),
+            Err(Error::NotAFile {
+                option: "--config",
+                path: PathBuf::from("/nonexistent/visualnv.toml")
+            })
+        );
+
+        options.config = None;
+        options.target = Some(Target::EnvFile(std::env::temp_dir()));
+        assert!(matches!(validate(&options), Err(Error::NotAFile { option: "--env-file", .. })));
+    }
+
+    #[test]
+    fn the_command_line_overrides_the_config() {
+        let mut config = Config::new();
+        config.view = String::from("cell");
+        config.theme = Some(String::from("dark"));
+
+        options(&[]).apply(&mut config);
+        assert_eq!(config.view, "cell");
+
+        options(&["--view", "home", "--theme", "light"]).apply(&mut config);
+        assert_eq!(config.view, "home");
+        assert_eq!(config.theme.as_deref(), Some("light"));
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(Error::Requires("--size", "--print").to_string(), "--size only applies to --print");
+        assert_eq!(
+            parse_strs(&["--format", "png"]).unwrap_err().to_string(),
+            "invalid value \"png\" for --format, expected text, html or svg"
+        );
+    }
+}
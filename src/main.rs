@@ -1,7 +1,159 @@
 mod app;
+mod cli;
 
-use app::App;
+use std::{
+    env,
+    error::Error,
+    fs,
+    path::PathBuf,
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
-fn main() -> Result<(), app::Error> {
-    App::new()?.run()
+use app::{
+    parse_env, view_by_name, App, Config, ConfigWatcher, InspectView, Logger, PrintScreen, ScreenOptions,
+    View,
+};
+use cli::{Command, Options, Target};
+
+fn main() -> ExitCode {
+    // Everything that can be wrong with the arguments is reported while the terminal is untouched
+    let options = match cli::parse(env::args().skip(1)).and_then(|command| {
+        if let Command::Run(options) = &command {
+            cli::validate(options)?;
+        }
+        Ok(command)
+    }) {
+        Ok(Command::Help) => {
+            print!("{}", cli::help());
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Version) => {
+            println!("visualnv {}", cli::version());
+            return ExitCode::SUCCESS;
+        }
+        Ok(Command::Run(options)) => options,
+        Err(err) => {
+            eprintln!("visualnv: {err}\n{}", cli::usage());
+            return ExitCode::from(2);
+        }
+    };
+    let config_path = options.config.clone().or_else(Config::find);
+    let mut config = match &config_path {
+        Some(path) => match Config::load(path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("visualnv: {err}");
+                return ExitCode::from(2);
+            }
+        },
+        None => Config::new(),
+    };
+    options.apply(&mut config);
+    // The file only knows about its own theme, --theme is checked here
+    if let Err(message) = config.get_theme() {
+        eprintln!("visualnv: {message}");
+        return ExitCode::from(2);
+    }
+
+    let mut logger = match &options.log {
+        Some(path) => match Logger::to_file(path) {
+            Ok(logger) => logger,
+            Err(err) => {
+                eprintln!("visualnv: cannot open the log file {}: {err}", path.display());
+                return ExitCode::from(2);
+            }
+        },
+        None => Logger::none(),
+    };
+
+    if let Some(path) = &config_path {
+        logger.log(format_args!("config loaded from {}", path.display()));
+    }
+    let root_view = match &options.target {
+        Some(target) => match inspect_view(target) {
+            Ok(view) => view,
+            Err(err) => {
+                eprintln!("visualnv: cannot read {target}: {err}");
+                return ExitCode::from(2);
+            }
+        },
+        // The name was checked while parsing the arguments and the config
+        None => view_by_name(&config.view).unwrap(),
+    };
+
+    // The app, and with it the terminal, is restored before the error is printed
+    match run(&options, &config, root_view, config_path, logger) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("visualnv: {err}");
+            let mut source = err.source();
+            while let Some(cause) = source {
+                eprintln!("  caused by: {cause}");
+                source = cause.source();
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/**
+ * How often the config file is checked for changes while the app runs
+ */
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/**
+ * A command is not run, it is shown with the environment of visualnv, which it would inherit
+ */
+fn inspect_view(target: &Target) -> std::io::Result<Box<dyn View>> {
+    let variables = match target {
+        Target::EnvFile(path) => parse_env(&fs::read_to_string(path)?),
+        Target::Command(_) => {
+            let mut variables: Vec<(String, String)> = env::vars().collect();
+            variables.sort();
+            variables
+        }
+    };
+    Ok(Box::new(InspectView::from(target.to_string(), variables)))
+}
+
+fn run(
+    options: &Options,
+    config: &Config,
+    root_view: Box<dyn View>,
+    config_path: Option<PathBuf>,
+    mut logger: Logger,
+) -> Result<(), app::Error> {
+    if let Some(theme) = &config.theme {
+        logger.log(format_args!("theme {theme}"));
+    }
+    if let Some(target) = &options.target {
+        logger.log(format_args!("inspecting {target}"));
+    }
+
+    match &options.print {
+        Some(print) => {
+            let screen = PrintScreen::with_size(print.cols, print.rows).with_format(print.format);
+            App::with_root_view(screen, root_view)
+                .with_config(config)
+                .with_logger(logger)
+                .render_once()
+        }
+        None => {
+            let screen_options = ScreenOptions {
+                alt_screen: options.alt_screen,
+                color_depth: config.get_color_depth(),
+            };
+            logger.log(format_args!("color depth {}", screen_options.color_depth));
+            let mut app = App::new(&screen_options, root_view)?.with_config(config);
+            if let Some(path) = config_path {
+                // The command line keeps overriding the file after every reload
+                let overrides = options.clone();
+                let watcher = ConfigWatcher::from(path, CONFIG_CHECK_INTERVAL, Instant::now())
+                    .with_adjustment(move |config| overrides.apply(config));
+                app = app.with_config_watcher(watcher);
+            }
+            app.with_logger(logger).run()
+        }
+    }
 }